@@ -0,0 +1,295 @@
+//! Lower bounds on the number of bins needed to pack a set of items.
+//!
+//! These can be used to judge how good the result of one of the heuristics is:
+//! if a packing uses as many bins as a lower bound, it is optimal.
+//!
+//! Items larger than the bin size can't share a bin with anything,
+//! so every bound counts them as a bin of their own.
+
+use crate::{Pack, Size};
+
+/// The L1 lower bound: the total size of the items divided by the bin size, rounded up.
+pub fn l1<T, S>(bin_size: S, items: &[T]) -> usize
+where
+    T: Pack<S>,
+    S: Size,
+{
+    l1_by_key(bin_size, items, T::size)
+}
+
+/// The L1 lower bound: the total size of the items divided by the bin size, rounded up.
+///
+/// Unlike [`l1`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+pub fn l1_by_key<T, S, SizeFunc>(bin_size: S, items: &[T], key_func: SizeFunc) -> usize
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    assert!(bin_size > S::ZERO, "Bin size must be greater than 0");
+
+    let (oversized, total_size) =
+        items
+            .iter()
+            .map(key_func)
+            .fold((0, S::ZERO), |(oversized, total_size), size| {
+                if size > bin_size {
+                    (oversized + 1, total_size)
+                } else {
+                    (oversized, total_size + size)
+                }
+            });
+
+    oversized + total_size.div_ceil(bin_size)
+}
+
+/// The L2 lower bound by Martello and Toth.
+///
+/// This bound dominates [`l1`], and is especially tighter when many items are bigger than half the bin.
+pub fn l2<T, S>(bin_size: S, items: &[T]) -> usize
+where
+    T: Pack<S>,
+    S: Size,
+{
+    l2_by_key(bin_size, items, T::size)
+}
+
+/// The L2 lower bound by Martello and Toth.
+///
+/// Unlike [`l2`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+pub fn l2_by_key<T, S, SizeFunc>(bin_size: S, items: &[T], key_func: SizeFunc) -> usize
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    assert!(bin_size > S::ZERO, "Bin size must be greater than 0");
+
+    let (oversized, sizes) = sorted_sizes(bin_size, items, key_func);
+
+    oversized + __internal_l2(bin_size, &sizes)
+}
+
+/// The L3 lower bound by Martello and Toth.
+///
+/// This bound dominates [`l2`], but is more expensive to compute:
+/// it repeatedly fixes bins whose contents are known to be part of an optimal solution,
+/// drops the smallest item that is left, and computes [`l2`] for what remains.
+pub fn l3<T, S>(bin_size: S, items: &[T]) -> usize
+where
+    T: Pack<S>,
+    S: Size,
+{
+    l3_by_key(bin_size, items, T::size)
+}
+
+/// The L3 lower bound by Martello and Toth.
+///
+/// Unlike [`l3`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+pub fn l3_by_key<T, S, SizeFunc>(bin_size: S, items: &[T], key_func: SizeFunc) -> usize
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    assert!(bin_size > S::ZERO, "Bin size must be greater than 0");
+
+    let (oversized, sizes) = sorted_sizes(bin_size, items, key_func);
+
+    oversized + __internal_l3(bin_size, sizes)
+}
+
+/// Get the sizes of the items that fit in a bin in decreasing order,
+/// together with the number of items that don't.
+fn sorted_sizes<T, S, SizeFunc>(bin_size: S, items: &[T], key_func: SizeFunc) -> (usize, Vec<S>)
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    let mut sizes = items.iter().map(key_func).collect::<Vec<_>>();
+    sizes.sort_unstable_by(|a, b| b.total_cmp(a));
+
+    let oversized = sizes.iter().take_while(|&&size| size > bin_size).count();
+    sizes.drain(..oversized);
+
+    (oversized, sizes)
+}
+
+/// The L2 bound for sizes that all fit in a bin.
+///
+/// For every threshold `alpha`, the items larger than `bin_size - alpha` and the items larger
+/// than half the bin each need their own bin, and the items between `alpha` and half the bin
+/// have to fit in the space the latter leave behind.
+#[doc(hidden)]
+pub(crate) fn __internal_l2<S>(bin_size: S, sizes: &[S]) -> usize
+where
+    S: Size,
+{
+    let mut alphas = sizes
+        .iter()
+        .copied()
+        .filter(|&size| size <= bin_size - size)
+        .collect::<Vec<_>>();
+    alphas.push(S::ZERO);
+    alphas.sort_unstable_by(S::total_cmp);
+    alphas.dedup();
+
+    alphas
+        .into_iter()
+        .map(|alpha| {
+            let mut large = 0;
+            let mut medium = 0;
+            let mut medium_free = S::ZERO;
+            let mut small_size = S::ZERO;
+
+            for &size in sizes {
+                if size > bin_size - alpha {
+                    large += 1;
+                } else if size > bin_size - size {
+                    medium += 1;
+                    medium_free = medium_free + (bin_size - size);
+                } else if size >= alpha {
+                    small_size = small_size + size;
+                }
+            }
+
+            large + medium + small_size.saturating_sub(medium_free).div_ceil(bin_size)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// The L3 bound for decreasingly sorted sizes that all fit in a bin.
+#[doc(hidden)]
+pub(crate) fn __internal_l3<S>(bin_size: S, mut sizes: Vec<S>) -> usize
+where
+    S: Size,
+{
+    let mut fixed = 0;
+    let mut bound = 0;
+
+    loop {
+        let (reduced, remaining) = reduce(bin_size, &sizes);
+        fixed += reduced;
+        bound = bound.max(fixed + __internal_l2(bin_size, &remaining));
+
+        sizes = remaining;
+        if sizes.pop().is_none() {
+            return bound;
+        }
+    }
+}
+
+/// A simplified version of the reduction procedure MTRP by Martello and Toth.
+///
+/// Going from the largest item to the smallest, an item is fixed in a bin if:
+/// - no other item fits with it,
+/// - it fills up a bin exactly with the largest item that fits with it, or
+/// - only one other item at a time fits with it, in which case it is put with the largest one.
+///
+/// There is always an optimal solution that contains these bins.
+/// Returns the number of bins that were fixed, and the (decreasingly sorted) sizes that are left.
+fn reduce<S>(bin_size: S, sizes: &[S]) -> (usize, Vec<S>)
+where
+    S: Size,
+{
+    let mut fixed = vec![false; sizes.len()];
+    let mut bins = 0;
+
+    for j in 0..sizes.len() {
+        if fixed[j] {
+            continue;
+        }
+
+        let room = bin_size - sizes[j];
+        let free = (0..sizes.len()).filter(|&i| i != j && !fixed[i]);
+
+        let Some(k) = free.clone().find(|&i| sizes[i] <= room) else {
+            fixed[j] = true;
+            bins += 1;
+            continue;
+        };
+
+        // The two smallest free items are at the end
+        let mut smallest = free.rev().take(2).map(|i| sizes[i]);
+        let pair_fits = match (smallest.next(), smallest.next()) {
+            (Some(a), Some(b)) => a + b <= room,
+            _ => false,
+        };
+
+        if sizes[k] == room || !pair_fits {
+            fixed[j] = true;
+            fixed[k] = true;
+            bins += 1;
+        }
+    }
+
+    let remaining = sizes
+        .iter()
+        .zip(fixed)
+        .filter(|(_, fixed)| !fixed)
+        .map(|(&size, _)| size)
+        .collect();
+
+    (bins, remaining)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{generate_test_set_a, MyItem};
+
+    fn items(sizes: &[usize]) -> Vec<MyItem> {
+        sizes.iter().map(|&size| MyItem { size }).collect()
+    }
+
+    #[test]
+    fn test_dataset_a() {
+        let (test_data, bin_size) = generate_test_set_a();
+
+        // The optimal solution uses 4 bins
+        assert_eq!(4, l1(bin_size, &test_data));
+        assert_eq!(4, l2(bin_size, &test_data));
+        assert_eq!(4, l3(bin_size, &test_data));
+    }
+
+    #[test]
+    fn bounds_get_tighter() {
+        let test_data = items(&[60, 60, 60, 10]);
+        assert_eq!(2, l1(100, &test_data));
+        assert_eq!(3, l2(100, &test_data));
+
+        let test_data = items(&[10, 10, 10, 6, 6, 6, 3]);
+        assert_eq!(3, l1(17, &test_data));
+        assert_eq!(3, l2(17, &test_data));
+        assert_eq!(4, l3(17, &test_data));
+    }
+
+    #[test]
+    fn oversized_items_need_their_own_bin() {
+        let test_data = items(&[50, 5, 5]);
+
+        assert_eq!(2, l1(20, &test_data));
+        assert_eq!(2, l2(20, &test_data));
+        assert_eq!(2, l3(20, &test_data));
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let test_data = items(&[10, 10, 10, 6, 6, 6, 3])
+            .into_iter()
+            .map(|item| item.make_unpacked())
+            .collect::<Vec<_>>();
+
+        assert_eq!(3, l1_by_key(17, &test_data, |item| item.size));
+        assert_eq!(3, l2_by_key(17, &test_data, |item| item.size));
+        assert_eq!(4, l3_by_key(17, &test_data, |item| item.size));
+    }
+
+    #[test]
+    fn empty_input_needs_no_bins() {
+        assert_eq!(0, l1::<MyItem, _>(10, &[]));
+        assert_eq!(0, l2::<MyItem, _>(10, &[]));
+        assert_eq!(0, l3::<MyItem, _>(10, &[]));
+    }
+}
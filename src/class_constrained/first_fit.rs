@@ -0,0 +1,233 @@
+use crate::{Bin, Pack, Size};
+
+use crate::online::online_packer::OnlinePackerError;
+use crate::online::OnlinePacker;
+
+use super::{assert_valid_max_classes, ClassBin};
+
+/// Pack items in bins using the [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing)
+/// bin packing algorithm, putting items of at most `max_classes` distinct classes in every bin.
+///
+/// Every item is put in the first bin that it fits in,
+/// and that either already holds an item of the same class or holds fewer than `max_classes` classes.
+/// The class of an item is determined by `class_func`.
+///
+/// Items that are too large for a bin are put in a bin of their own.
+///
+/// Panics if `bin_size` or `max_classes` is 0.
+pub fn first_fit<T, S, C, ClassFunc>(
+    bin_size: S,
+    max_classes: usize,
+    items: impl IntoIterator<Item = T>,
+    class_func: ClassFunc,
+) -> Vec<Bin<T, S>>
+where
+    T: Pack<S>,
+    S: Size,
+    C: PartialEq,
+    ClassFunc: Fn(&T) -> C,
+{
+    first_fit_by_key(bin_size, max_classes, items, T::size, class_func)
+}
+
+/// Pack items in bins using the [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing)
+/// bin packing algorithm, putting items of at most `max_classes` distinct classes in every bin.
+///
+/// Unlike [`first_fit`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+pub fn first_fit_by_key<T, S, C, SizeFunc, ClassFunc>(
+    bin_size: S,
+    max_classes: usize,
+    items: impl IntoIterator<Item = T>,
+    key_func: SizeFunc,
+    class_func: ClassFunc,
+) -> Vec<Bin<T, S>>
+where
+    S: Size,
+    C: PartialEq,
+    SizeFunc: Fn(&T) -> S,
+    ClassFunc: Fn(&T) -> C,
+{
+    assert!(bin_size > S::ZERO, "Bin size must be greater than 0");
+    assert_valid_max_classes(max_classes);
+
+    let mut bins = Vec::new();
+    for item in items {
+        let size = key_func(&item);
+        let class = class_func(&item);
+        super::place_first_fit(&mut bins, bin_size, max_classes, item, size, class);
+    }
+
+    bins.into_iter().map(|bin| bin.bin).collect()
+}
+
+/// This implements the [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing)
+/// bin packing algorithm, putting items of at most `max_classes` distinct classes in every bin.
+///
+/// When a new item arrives, it is put into the first open bin that it fits in,
+/// and that either already holds an item of the same class or holds fewer than `max_classes` classes.
+/// If there is no such bin, a new bin is opened to hold the new item.
+///
+/// A bin is only closed (and returned from [`OnlinePacker::try_add`]) once it is completely full.
+#[derive(Debug)]
+pub struct FirstFitPacker<Item, SizeFn, ClassFn, C, S = usize> {
+    bins: Vec<ClassBin<Item, C, S>>,
+    max_bin_size: S,
+    max_classes: usize,
+    size_fn: SizeFn,
+    class_fn: ClassFn,
+}
+
+impl<Item, SizeFn, ClassFn, C, S> FirstFitPacker<Item, SizeFn, ClassFn, C, S>
+where
+    S: Size,
+{
+    /// Create a new FirstFitPacker.
+    ///
+    /// Each bin will fit a maximum of `size`, and items of at most `max_classes` distinct classes.
+    ///
+    /// The size of a single element is determined by the `size_fn`,
+    /// and its class by the `class_fn`.
+    ///
+    /// Panics if `size` or `max_classes` is 0.
+    pub fn new_with_key(size: S, max_classes: usize, size_fn: SizeFn, class_fn: ClassFn) -> Self {
+        assert!(size > S::ZERO, "size must be greater than 0");
+        assert_valid_max_classes(max_classes);
+
+        Self {
+            bins: Vec::new(),
+            max_bin_size: size,
+            max_classes,
+            size_fn,
+            class_fn,
+        }
+    }
+}
+
+impl<Item, ClassFn, C, S> FirstFitPacker<Item, fn(&Item) -> S, ClassFn, C, S>
+where
+    S: Size,
+{
+    /// Create a new FirstFitPacker.
+    ///
+    /// This function requires that `Item` implements [`Pack`].
+    /// If your type doesn't, consider using [`new_with_key`](FirstFitPacker::new_with_key).
+    pub fn new(size: S, max_classes: usize, class_fn: ClassFn) -> Self
+    where
+        Item: Pack<S>,
+    {
+        fn pack_size<S>(item: &impl Pack<S>) -> S {
+            item.size()
+        }
+
+        FirstFitPacker::<Item, _, ClassFn, C, S>::new_with_key(
+            size,
+            max_classes,
+            pack_size,
+            class_fn,
+        )
+    }
+}
+
+impl<Item, SizeFn, ClassFn, C, S> OnlinePacker<Item, S>
+    for FirstFitPacker<Item, SizeFn, ClassFn, C, S>
+where
+    SizeFn: Fn(&Item) -> S,
+    ClassFn: Fn(&Item) -> C,
+    C: PartialEq,
+    S: Size,
+{
+    fn try_add(&mut self, item: Item) -> Result<Vec<Bin<Item, S>>, OnlinePackerError<Item>> {
+        let item_size = (self.size_fn)(&item);
+        if item_size > self.max_bin_size {
+            return Err(OnlinePackerError::ItemTooLarge(item));
+        }
+
+        let class = (self.class_fn)(&item);
+        let bin_idx = super::place_first_fit(
+            &mut self.bins,
+            self.max_bin_size,
+            self.max_classes,
+            item,
+            item_size,
+            class,
+        );
+
+        // A full bin can't take any more items, so there is no reason to keep it open.
+        if self.bins[bin_idx].bin.remaining_capacity == S::ZERO {
+            return Ok(vec![self.bins.remove(bin_idx).bin]);
+        }
+
+        Ok(Vec::new())
+    }
+
+    fn finalize(self) -> Vec<Bin<Item, S>> {
+        self.bins.into_iter().map(|bin| bin.bin).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::class_constrained::tests::bin_classes;
+    use crate::tests::{generate_test_bins, generate_test_set_a, MyItem};
+
+    #[test]
+    fn it_works() {
+        let (test_data, bin_size) = generate_test_set_a();
+
+        // Odd and even sizes are different classes, and only one class is allowed per bin
+        let result = first_fit(bin_size, 1, test_data, |item: &MyItem| item.size % 2);
+
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![1, 1, 1, 1, 3], // 7
+                vec![4, 10],         // 14
+                vec![10, 10],        // 20
+                vec![19],            // 19
+                vec![19],            // 19
+            ],
+        );
+
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn limits_distinct_classes() {
+        let segments = vec![('h', 4), ('v', 3), ('a', 2), ('h', 2), ('a', 1)];
+
+        let result = first_fit_by_key(10, 2, segments, |&(_, size)| size, |&(codec, _)| codec);
+
+        assert_eq!(vec!["hvh", "aa"], bin_classes(&result));
+    }
+
+    #[test]
+    fn packer_closes_full_bins() {
+        let segments = vec![('h', 4), ('v', 3), ('a', 2), ('h', 3), ('a', 1), ('a', 7)];
+        let mut packer = FirstFitPacker::new_with_key(
+            10,
+            2,
+            |&(_, size): &(char, usize)| size,
+            |&(codec, _): &(char, usize)| codec,
+        );
+
+        let mut closed = Vec::new();
+        for segment in segments {
+            closed.extend(packer.add(segment));
+        }
+
+        assert_eq!(vec!["hvh", "aaa"], bin_classes(&closed));
+        assert!(packer.finalize().is_empty());
+    }
+
+    #[test]
+    fn packer_rejects_large_items() {
+        let mut packer = FirstFitPacker::new(10, 2, |item: &MyItem| item.size % 2);
+
+        assert_eq!(
+            Err(OnlinePackerError::ItemTooLarge(MyItem { size: 11 })),
+            packer.try_add(MyItem { size: 11 })
+        );
+    }
+}
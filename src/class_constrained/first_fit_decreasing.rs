@@ -0,0 +1,103 @@
+use crate::{Bin, Pack, Size};
+
+use super::{assert_valid_max_classes, place_first_fit};
+
+/// Pack items in bins using the [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing)
+/// bin packing algorithm, putting items of at most `max_classes` distinct classes in every bin.
+///
+/// The items are sorted in decreasing order, and every item is put in the first bin that it fits in,
+/// and that either already holds an item of the same class or holds fewer than `max_classes` classes.
+/// The class of an item is determined by `class_func`.
+///
+/// Items that are too large for a bin are put in a bin of their own.
+///
+/// Panics if `bin_size` or `max_classes` is 0.
+pub fn first_fit_decreasing<T, S, C, ClassFunc>(
+    bin_size: S,
+    max_classes: usize,
+    items: Vec<T>,
+    class_func: ClassFunc,
+) -> Vec<Bin<T, S>>
+where
+    T: Pack<S>,
+    S: Size,
+    C: PartialEq,
+    ClassFunc: Fn(&T) -> C,
+{
+    first_fit_decreasing_by_key(bin_size, max_classes, items, T::size, class_func)
+}
+
+/// Pack items in bins using the [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing)
+/// bin packing algorithm, putting items of at most `max_classes` distinct classes in every bin.
+///
+/// Unlike [`first_fit_decreasing`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+pub fn first_fit_decreasing_by_key<T, S, C, SizeFunc, ClassFunc>(
+    bin_size: S,
+    max_classes: usize,
+    items: Vec<T>,
+    key_func: SizeFunc,
+    class_func: ClassFunc,
+) -> Vec<Bin<T, S>>
+where
+    S: Size,
+    C: PartialEq,
+    SizeFunc: Fn(&T) -> S,
+    ClassFunc: Fn(&T) -> C,
+{
+    assert!(bin_size > S::ZERO, "Bin size must be greater than 0");
+    assert_valid_max_classes(max_classes);
+
+    // Sort the items in decreasing order
+    let mut items = items
+        .into_iter()
+        .map(|item| (key_func(&item), item))
+        .collect::<Vec<_>>();
+    items.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+    let mut bins = Vec::new();
+    for (size, item) in items {
+        let class = class_func(&item);
+        place_first_fit(&mut bins, bin_size, max_classes, item, size, class);
+    }
+
+    bins.into_iter().map(|bin| bin.bin).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::class_constrained::tests::bin_classes;
+    use crate::tests::{generate_test_bins, generate_test_set_a, MyItem};
+
+    #[test]
+    fn it_works() {
+        let (test_data, bin_size) = generate_test_set_a();
+
+        // Odd and even sizes are different classes, and only one class is allowed per bin
+        let result = first_fit_decreasing(bin_size, 1, test_data, |item: &MyItem| item.size % 2);
+
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![19, 1],   // 20
+                vec![19, 1],   // 20
+                vec![10, 10],  // 20
+                vec![10, 4],   // 14
+                vec![3, 1, 1], // 5
+            ],
+        );
+
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let items = vec![('a', 2), ('b', 6), ('c', 5), ('a', 4)];
+
+        let result =
+            first_fit_decreasing_by_key(10, 2, items, |&(_, size)| size, |&(class, _)| class);
+
+        assert_eq!(vec!["ba", "ca"], bin_classes(&result));
+    }
+}
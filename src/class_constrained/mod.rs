@@ -0,0 +1,94 @@
+//! Class-constrained bin packing, where every item has a class
+//! and every bin may hold items of at most `max_classes` distinct classes.
+//!
+//! The class of an item is determined by a function, and can be anything that can be compared,
+//! like a codec, a tenant or a color.
+//! Any number of items of a class that is already in a bin can still be put in it.
+
+pub mod first_fit;
+pub mod first_fit_decreasing;
+
+use crate::{Bin, Size};
+
+/// Panics if no classes at all would be allowed in a bin.
+fn assert_valid_max_classes(max_classes: usize) {
+    assert!(
+        max_classes > 0,
+        "Maximum number of classes must be greater than 0"
+    );
+}
+
+/// A bin, together with the distinct classes of the items in it.
+#[derive(Debug)]
+struct ClassBin<T, C, S> {
+    bin: Bin<T, S>,
+    classes: Vec<C>,
+}
+
+impl<T, C, S> ClassBin<T, C, S>
+where
+    C: PartialEq,
+    S: Size,
+{
+    fn new(bin_size: S) -> Self {
+        Self {
+            bin: Bin::with_capacity(bin_size),
+            classes: Vec::new(),
+        }
+    }
+
+    /// Check whether an item fits in this bin, both by size and by class.
+    fn admits(&self, size: S, class: &C, max_classes: usize) -> bool {
+        size <= self.bin.remaining_capacity
+            && (self.classes.contains(class) || self.classes.len() < max_classes)
+    }
+
+    fn add(&mut self, item: T, size: S, class: C) {
+        self.bin.add_with_size(item, size);
+        if !self.classes.contains(&class) {
+            self.classes.push(class);
+        }
+    }
+}
+
+/// Put every item in the first bin that admits it, opening a new bin if there is none.
+///
+/// Returns the index of the bin the last item was put in.
+fn place_first_fit<T, C, S>(
+    bins: &mut Vec<ClassBin<T, C, S>>,
+    bin_size: S,
+    max_classes: usize,
+    item: T,
+    size: S,
+    class: C,
+) -> usize
+where
+    C: PartialEq,
+    S: Size,
+{
+    let bin_idx = match bins
+        .iter()
+        .position(|bin| bin.admits(size, &class, max_classes))
+    {
+        Some(bin_idx) => bin_idx,
+        None => {
+            bins.push(ClassBin::new(bin_size));
+            bins.len() - 1
+        }
+    };
+
+    bins[bin_idx].add(item, size, class);
+    bin_idx
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::Bin;
+
+    /// The classes of the items in every bin, for items that are `(class, size)` pairs.
+    pub fn bin_classes(bins: &[Bin<(char, usize)>]) -> Vec<String> {
+        bins.iter()
+            .map(|bin| bin.iter().map(|&(class, _)| class).collect())
+            .collect()
+    }
+}
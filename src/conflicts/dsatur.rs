@@ -0,0 +1,122 @@
+use std::cmp::Ordering;
+
+use crate::{Bin, Pack, Size};
+
+use super::{Assignment, ConflictGraph};
+
+/// Pack items in bins using a heuristic based on the [DSatur](https://en.wikipedia.org/wiki/DSatur)
+/// graph coloring algorithm by Brélaz, never putting conflicting items in the same bin.
+///
+/// The item that conflicts with items in the most different bins is packed first,
+/// since it has the fewest bins left to choose from.
+/// Ties are broken by packing larger items first, and then items with more conflicts.
+/// Every item is put in the first bin that it fits in and that holds no item it conflicts with.
+///
+/// This usually needs fewer bins than [`first_fit_decreasing`](super::first_fit_decreasing)
+/// when there are many conflicts, at the cost of `O(n²)` running time.
+///
+/// Panics if `bin_size` is 0, or if `conflicts` doesn't have a node for every item.
+pub fn dsatur<T, S>(bin_size: S, items: Vec<T>, conflicts: &ConflictGraph) -> Vec<Bin<T, S>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    dsatur_by_key(bin_size, items, T::size, conflicts)
+}
+
+/// Pack items in bins using a heuristic based on the [DSatur](https://en.wikipedia.org/wiki/DSatur)
+/// graph coloring algorithm by Brélaz, never putting conflicting items in the same bin.
+///
+/// Unlike [`dsatur`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+pub fn dsatur_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: Vec<T>,
+    key_func: SizeFunc,
+    conflicts: &ConflictGraph,
+) -> Vec<Bin<T, S>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    let sizes = items.iter().map(key_func).collect::<Vec<_>>();
+    let mut assignment = Assignment::new(bin_size, &sizes, conflicts);
+
+    // The distinct bins that the conflicting items of every item are in
+    let mut saturation = vec![Vec::new(); sizes.len()];
+
+    let priority = |saturation: &[Vec<usize>], a: usize, b: usize| -> Ordering {
+        saturation[a]
+            .len()
+            .cmp(&saturation[b].len())
+            .then_with(|| sizes[a].total_cmp(&sizes[b]))
+            .then_with(|| {
+                conflicts
+                    .neighbors(a)
+                    .len()
+                    .cmp(&conflicts.neighbors(b).len())
+            })
+    };
+
+    let mut unpacked = (0..sizes.len()).collect::<Vec<_>>();
+    while !unpacked.is_empty() {
+        // Find the item with the highest priority, preferring the first one on ties
+        let pos = (1..unpacked.len()).fold(0, |best, pos| {
+            match priority(&saturation, unpacked[pos], unpacked[best]) {
+                Ordering::Greater => pos,
+                _ => best,
+            }
+        });
+        let idx = unpacked.remove(pos);
+
+        let bin_idx = assignment.first_fit(idx);
+        for &neighbor in conflicts.neighbors(idx) {
+            if !saturation[neighbor].contains(&bin_idx) {
+                saturation[neighbor].push(bin_idx);
+            }
+        }
+    }
+
+    assignment.into_bins(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conflicts::first_fit_decreasing::first_fit_decreasing_by_key;
+    use crate::conflicts::tests::bin_names;
+    use crate::tests::{generate_test_bins, generate_test_set_a};
+
+    #[test]
+    fn without_conflicts_matches_first_fit_decreasing() {
+        let (test_data, bin_size) = generate_test_set_a();
+        let conflicts = ConflictGraph::new(test_data.len());
+
+        let result = dsatur(bin_size, test_data, &conflicts);
+
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![19, 1],          // 20
+                vec![19, 1],          // 20
+                vec![10, 10],         // 20
+                vec![10, 4, 3, 1, 1], // 19
+            ],
+        );
+
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn packs_conflicting_items_first() {
+        let items = vec![('a', 3), ('b', 4), ('c', 5), ('d', 4)];
+        let conflicts = ConflictGraph::new(items.len()).with_conflict(0, 3);
+
+        let ffd = first_fit_decreasing_by_key(10, items.clone(), |&(_, size)| size, &conflicts);
+        assert_eq!(vec!["cb", "d", "a"], bin_names(&ffd));
+
+        // Of the two items of size 4, the one with a conflict is packed first
+        let result = dsatur_by_key(10, items, |&(_, size)| size, &conflicts);
+        assert_eq!(vec!["cd", "ab"], bin_names(&result));
+    }
+}
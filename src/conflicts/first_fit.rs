@@ -0,0 +1,84 @@
+use crate::{Bin, Pack, Size};
+
+use super::{Assignment, ConflictGraph};
+
+/// Pack items in bins using the [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing)
+/// bin packing algorithm, never putting conflicting items in the same bin.
+///
+/// Every item is put in the first bin that it fits in and that holds no item it conflicts with.
+///
+/// Panics if `bin_size` is 0, or if `conflicts` doesn't have a node for every item.
+pub fn first_fit<T, S>(bin_size: S, items: Vec<T>, conflicts: &ConflictGraph) -> Vec<Bin<T, S>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    first_fit_by_key(bin_size, items, T::size, conflicts)
+}
+
+/// Pack items in bins using the [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing)
+/// bin packing algorithm, never putting conflicting items in the same bin.
+///
+/// Unlike [`first_fit`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+pub fn first_fit_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: Vec<T>,
+    key_func: SizeFunc,
+    conflicts: &ConflictGraph,
+) -> Vec<Bin<T, S>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    let sizes = items.iter().map(key_func).collect::<Vec<_>>();
+
+    let mut assignment = Assignment::new(bin_size, &sizes, conflicts);
+    for idx in 0..sizes.len() {
+        assignment.first_fit(idx);
+    }
+
+    assignment.into_bins(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conflicts::tests::bin_names;
+    use crate::tests::{generate_test_bins, generate_test_set_a};
+
+    #[test]
+    fn it_works() {
+        let (test_data, bin_size) = generate_test_set_a();
+
+        // The first item of size 1 conflicts with the item of size 3
+        let conflicts = ConflictGraph::new(test_data.len()).with_conflict(0, 4);
+
+        let result = first_fit(bin_size, test_data, &conflicts);
+
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![1, 1, 1, 1, 4, 10], // 18
+                vec![3, 10],             // 13
+                vec![10],                // 10
+                vec![19],                // 19
+                vec![19],                // 19
+            ],
+        );
+
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn keeps_conflicting_items_apart() {
+        let items = vec![('a', 3), ('a', 3), ('b', 3), ('c', 3)];
+
+        // Replicas of the same shard must not share a bin
+        let conflicts = ConflictGraph::from_predicate(&items, |a, b| a.0 == b.0);
+
+        let result = first_fit_by_key(10, items, |&(_, size)| size, &conflicts);
+
+        assert_eq!(vec!["abc", "a"], bin_names(&result));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{Bin, Pack, Size};
+
+use super::{Assignment, ConflictGraph};
+
+/// Pack items in bins using the [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing)
+/// bin packing algorithm, never putting conflicting items in the same bin.
+///
+/// The items are sorted in decreasing order, and every item is put in the first bin
+/// that it fits in and that holds no item it conflicts with.
+/// Items of the same size keep their order.
+///
+/// Panics if `bin_size` is 0, or if `conflicts` doesn't have a node for every item.
+pub fn first_fit_decreasing<T, S>(
+    bin_size: S,
+    items: Vec<T>,
+    conflicts: &ConflictGraph,
+) -> Vec<Bin<T, S>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    first_fit_decreasing_by_key(bin_size, items, T::size, conflicts)
+}
+
+/// Pack items in bins using the [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing)
+/// bin packing algorithm, never putting conflicting items in the same bin.
+///
+/// Unlike [`first_fit_decreasing`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+pub fn first_fit_decreasing_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: Vec<T>,
+    key_func: SizeFunc,
+    conflicts: &ConflictGraph,
+) -> Vec<Bin<T, S>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    let sizes = items.iter().map(key_func).collect::<Vec<_>>();
+    let mut assignment = Assignment::new(bin_size, &sizes, conflicts);
+
+    // Go over the items in decreasing order
+    let mut order = (0..sizes.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| sizes[b].total_cmp(&sizes[a]));
+
+    for idx in order {
+        assignment.first_fit(idx);
+    }
+
+    assignment.into_bins(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conflicts::tests::bin_names;
+    use crate::tests::{generate_test_bins, generate_test_set_a};
+
+    #[test]
+    fn it_works() {
+        let (test_data, bin_size) = generate_test_set_a();
+
+        // The first item of size 19 conflicts with every item of size 1
+        let conflicts = (0..4).fold(ConflictGraph::new(test_data.len()), |graph, idx| {
+            graph.with_conflict(idx, 9)
+        });
+
+        let result = first_fit_decreasing(bin_size, test_data, &conflicts);
+
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![19],                // 19
+                vec![19, 1],             // 20
+                vec![10, 10],            // 20
+                vec![10, 4, 3, 1, 1, 1], // 20
+            ],
+        );
+
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let items = vec![('a', 2), ('b', 5), ('c', 5), ('d', 3)];
+        let conflicts = ConflictGraph::from_predicate(&items, |a, b| a.1 + b.1 == 5);
+
+        let result = first_fit_decreasing_by_key(10, items, |&(_, size)| size, &conflicts);
+
+        assert_eq!(vec!["bc", "d", "a"], bin_names(&result));
+    }
+}
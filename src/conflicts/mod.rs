@@ -0,0 +1,205 @@
+//! Bin packing with conflicts, where some pairs of items must never be put in the same bin,
+//! like replicas of the same shard.
+//!
+//! The conflicts are given as a [`ConflictGraph`] over the positions of the items,
+//! which can also be built from a predicate with [`ConflictGraph::from_predicate`].
+//!
+//! Items that are too large for a bin are put in a bin of their own.
+
+pub mod dsatur;
+pub mod first_fit;
+pub mod first_fit_decreasing;
+
+use crate::{Bin, Size};
+
+/// An undirected graph with a node for every item, and an edge between every pair of items
+/// that must not be put in the same bin.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ConflictGraph {
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl ConflictGraph {
+    /// Create a graph for `len` items without any conflicts.
+    pub fn new(len: usize) -> Self {
+        Self {
+            neighbors: vec![Vec::new(); len],
+        }
+    }
+
+    /// Create a graph with a conflict between every pair of items for which `conflicts` returns `true`.
+    ///
+    /// The predicate is called once for every pair of items, with the first item coming before the second.
+    pub fn from_predicate<T>(items: &[T], conflicts: impl Fn(&T, &T) -> bool) -> Self {
+        let mut graph = Self::new(items.len());
+        for (a, item_a) in items.iter().enumerate() {
+            for (b, item_b) in items.iter().enumerate().skip(a + 1) {
+                if conflicts(item_a, item_b) {
+                    graph.add_conflict(a, b);
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Make sure the items at positions `a` and `b` are never put in the same bin.
+    ///
+    /// Panics if either position is out of range, or if `a` and `b` are the same.
+    pub fn add_conflict(&mut self, a: usize, b: usize) {
+        assert!(
+            a < self.len() && b < self.len(),
+            "Conflict between items {a} and {b}, but there are only {} items",
+            self.len()
+        );
+        assert_ne!(a, b, "An item can't conflict with itself");
+
+        if !self.conflicts(a, b) {
+            self.neighbors[a].push(b);
+            self.neighbors[b].push(a);
+        }
+    }
+
+    /// Add a conflict between the items at positions `a` and `b`, see [`add_conflict`](Self::add_conflict).
+    pub fn with_conflict(mut self, a: usize, b: usize) -> Self {
+        self.add_conflict(a, b);
+        self
+    }
+
+    /// Check whether the items at positions `a` and `b` conflict.
+    pub fn conflicts(&self, a: usize, b: usize) -> bool {
+        self.neighbors[a].contains(&b)
+    }
+
+    /// The positions of the items that conflict with the item at position `idx`.
+    pub fn neighbors(&self, idx: usize) -> &[usize] {
+        &self.neighbors[idx]
+    }
+
+    /// The number of items in the graph.
+    pub fn len(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    /// Check whether the graph has no items.
+    pub fn is_empty(&self) -> bool {
+        self.neighbors.is_empty()
+    }
+}
+
+/// Keeps track of which item is in which bin while packing.
+struct Assignment<'a, S> {
+    bin_size: S,
+    sizes: &'a [S],
+    graph: &'a ConflictGraph,
+    /// The bin every item is in, if it has been packed already.
+    bin_of: Vec<Option<usize>>,
+    /// The remaining capacity and the items of every bin.
+    bins: Vec<(S, Vec<usize>)>,
+}
+
+impl<'a, S> Assignment<'a, S>
+where
+    S: Size,
+{
+    /// Panics if the bin size is 0 or if the graph doesn't match the items.
+    fn new(bin_size: S, sizes: &'a [S], graph: &'a ConflictGraph) -> Self {
+        assert!(bin_size > S::ZERO, "Bin size must be greater than 0");
+        assert_eq!(
+            sizes.len(),
+            graph.len(),
+            "Conflict graph must have one node per item"
+        );
+
+        Self {
+            bin_size,
+            sizes,
+            graph,
+            bin_of: vec![None; sizes.len()],
+            bins: Vec::new(),
+        }
+    }
+
+    /// Put the item in the first bin it fits in without conflicts, returning the index of that bin.
+    fn first_fit(&mut self, idx: usize) -> usize {
+        let mut blocked = vec![false; self.bins.len()];
+        for &neighbor in self.graph.neighbors(idx) {
+            if let Some(bin_idx) = self.bin_of[neighbor] {
+                blocked[bin_idx] = true;
+            }
+        }
+
+        let size = self.sizes[idx];
+        let bin_idx = match (0..self.bins.len())
+            .find(|&bin_idx| !blocked[bin_idx] && size <= self.bins[bin_idx].0)
+        {
+            Some(bin_idx) => bin_idx,
+            None => {
+                self.bins.push((self.bin_size, Vec::new()));
+                self.bins.len() - 1
+            }
+        };
+
+        let (remaining_capacity, items) = &mut self.bins[bin_idx];
+        *remaining_capacity = remaining_capacity.saturating_sub(size);
+        items.push(idx);
+        self.bin_of[idx] = Some(bin_idx);
+
+        bin_idx
+    }
+
+    /// Put the items in the bins they were assigned to.
+    fn into_bins<T>(self, items: Vec<T>) -> Vec<Bin<T, S>> {
+        let mut items = items.into_iter().map(Some).collect::<Vec<_>>();
+
+        self.bins
+            .into_iter()
+            .map(|(_, indices)| {
+                let mut bin = Bin::with_capacity(self.bin_size);
+                for idx in indices {
+                    let item = items[idx].take().expect("Item is in more than one bin");
+                    bin.add_with_size(item, self.sizes[idx]);
+                }
+                bin
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// The names of the items in every bin, for items that are `(name, size)` pairs.
+    pub fn bin_names(bins: &[Bin<(char, usize)>]) -> Vec<String> {
+        bins.iter()
+            .map(|bin| bin.iter().map(|&(name, _)| name).collect())
+            .collect()
+    }
+
+    #[test]
+    fn graph_from_predicate() {
+        let items = [1, 2, 3, 4, 5, 6];
+
+        // Items with the same remainder modulo 3 conflict
+        let graph = ConflictGraph::from_predicate(&items, |a, b| a % 3 == b % 3);
+
+        assert!(graph.conflicts(0, 3));
+        assert!(graph.conflicts(3, 0));
+        assert!(!graph.conflicts(0, 1));
+        assert_eq!(&[5], graph.neighbors(2));
+        assert_eq!(
+            graph,
+            ConflictGraph::new(6)
+                .with_conflict(0, 3)
+                .with_conflict(1, 4)
+                .with_conflict(5, 2)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Conflict graph must have one node per item")]
+    fn graph_must_match_items() {
+        first_fit::first_fit_by_key(10usize, vec![1, 2, 3], |&size| size, &ConflictGraph::new(2));
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{Bin, Pack, Size};
+
+use super::{assert_valid_threshold, Covering};
+
+/// Cover bins using a heuristic based on [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing).
+///
+/// The items are sorted in decreasing order.
+/// Every bin is filled with the largest remaining items as long as they don't reach the `threshold` yet,
+/// and is then finished with the smallest remaining item that does,
+/// which keeps the amount by which it goes over the threshold small.
+///
+/// The items in the last bin are returned as leftovers if they don't reach the threshold.
+///
+/// Panics if `threshold` is 0.
+pub fn first_fit_decreasing<T, S>(threshold: S, items: Vec<T>) -> Covering<T, S>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    first_fit_decreasing_by_key(threshold, items, T::size)
+}
+
+/// Cover bins using a heuristic based on [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing).
+///
+/// Unlike [`first_fit_decreasing`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+pub fn first_fit_decreasing_by_key<T, S, SizeFunc>(
+    threshold: S,
+    items: Vec<T>,
+    key_func: SizeFunc,
+) -> Covering<T, S>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    assert_valid_threshold(threshold);
+
+    // Sort the items in increasing order, so the largest one can be popped off the end
+    let mut items = items
+        .into_iter()
+        .map(|item| (key_func(&item), item))
+        .collect::<Vec<_>>();
+    items.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    let mut bins = Vec::new();
+    let mut bin = Bin::with_capacity(threshold);
+
+    while let Some(&(largest, _)) = items.last() {
+        if largest < bin.remaining_capacity {
+            let (size, item) = items.pop().expect("There is at least one item");
+            bin.add_with_size(item, size);
+            continue;
+        }
+
+        // Finish the bin with the smallest item that covers it
+        let idx = items.partition_point(|&(size, _)| size < bin.remaining_capacity);
+        let (size, item) = items.remove(idx);
+        bin.add_with_size(item, size);
+        bins.push(std::mem::replace(&mut bin, Bin::with_capacity(threshold)));
+    }
+
+    Covering {
+        bins,
+        leftover: bin.into_contents(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::covering::next_fit::next_fit;
+    use crate::covering::tests::{bin_sizes, items};
+    use crate::tests::generate_test_set_a;
+
+    #[test]
+    fn it_works() {
+        let (test_data, threshold) = generate_test_set_a();
+
+        let result = first_fit_decreasing(threshold, test_data);
+
+        assert_eq!(
+            vec![
+                vec![19, 1],  // 20
+                vec![19, 1],  // 20
+                vec![10, 10], // 20
+            ],
+            bin_sizes(&result.bins)
+        );
+        assert_eq!(items(&[10, 4, 3, 1, 1]), result.leftover);
+    }
+
+    #[test]
+    fn improves_on_next_fit() {
+        let test_data = [1, 1, 1, 1, 9, 9, 9, 9];
+
+        assert_eq!(2, next_fit(10, items(&test_data)).bins.len());
+
+        let result = first_fit_decreasing(10, items(&test_data));
+
+        assert_eq!(vec![vec![9, 1]; 4], bin_sizes(&result.bins));
+        assert!(result.leftover.is_empty());
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let result = first_fit_decreasing_by_key(10u64, vec![6, 5, 4, 3, 2], |&size| size);
+
+        assert_eq!(&[6, 4], result.bins[0].contents());
+        assert_eq!(&[5, 3, 2], result.bins[1].contents());
+        assert!(result.leftover.is_empty());
+    }
+}
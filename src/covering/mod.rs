@@ -0,0 +1,44 @@
+//! [Bin covering](https://en.wikipedia.org/wiki/Bin_covering_problem), the dual of bin packing:
+//! items are put in bins so that as many bins as possible are filled to at least a threshold.
+//!
+//! The capacity of every bin is the threshold,
+//! so the [overflow](crate::Bin::overflow) of a bin is how far it goes over the threshold.
+
+pub mod first_fit_decreasing;
+pub mod next_fit;
+
+use crate::{Bin, Size};
+
+/// The result of a bin covering algorithm.
+#[derive(Debug)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+pub struct Covering<T, S = usize> {
+    /// The bins that are filled to at least the threshold.
+    pub bins: Vec<Bin<T, S>>,
+    /// The items that weren't enough to fill another bin.
+    pub leftover: Vec<T>,
+}
+
+/// Panics if the threshold is 0, since then every bin would be covered without any items.
+fn assert_valid_threshold<S>(threshold: S)
+where
+    S: Size,
+{
+    assert!(threshold > S::ZERO, "Threshold must be greater than 0");
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::tests::MyItem;
+    use crate::Bin;
+
+    pub fn items(sizes: &[usize]) -> Vec<MyItem> {
+        sizes.iter().map(|&size| MyItem { size }).collect()
+    }
+
+    pub fn bin_sizes(bins: &[Bin<MyItem>]) -> Vec<Vec<usize>> {
+        bins.iter()
+            .map(|bin| bin.iter().map(|item| item.size).collect())
+            .collect()
+    }
+}
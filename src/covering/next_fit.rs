@@ -0,0 +1,97 @@
+use crate::{Bin, Pack, Size};
+
+use super::{assert_valid_threshold, Covering};
+
+/// Cover bins using the Next-fit algorithm.
+///
+/// The items are put in a single open bin in the order they are given,
+/// until the bin is filled to at least the `threshold`; then a new bin is opened.
+/// This covers at least half as many bins as the optimal solution.
+///
+/// The items in the last bin are returned as leftovers if they don't reach the threshold.
+///
+/// Panics if `threshold` is 0.
+pub fn next_fit<T, S>(threshold: S, items: impl IntoIterator<Item = T>) -> Covering<T, S>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    next_fit_by_key(threshold, items, T::size)
+}
+
+/// Cover bins using the Next-fit algorithm.
+///
+/// Unlike [`next_fit`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+pub fn next_fit_by_key<T, S, SizeFunc>(
+    threshold: S,
+    items: impl IntoIterator<Item = T>,
+    key_func: SizeFunc,
+) -> Covering<T, S>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    assert_valid_threshold(threshold);
+
+    let mut bins = Vec::new();
+    let mut bin = Bin::with_capacity(threshold);
+
+    for item in items {
+        let size = key_func(&item);
+        bin.add_with_size(item, size);
+
+        if bin.remaining_capacity == S::ZERO {
+            bins.push(std::mem::replace(&mut bin, Bin::with_capacity(threshold)));
+        }
+    }
+
+    Covering {
+        bins,
+        leftover: bin.into_contents(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::covering::tests::{bin_sizes, items};
+    use crate::tests::generate_test_set_a;
+
+    #[test]
+    fn it_works() {
+        let (test_data, threshold) = generate_test_set_a();
+
+        let result = next_fit(threshold, test_data);
+
+        assert_eq!(
+            vec![
+                vec![1, 1, 1, 1, 3, 4, 10], // 21
+                vec![10, 10],               // 20
+                vec![19, 19],               // 38
+            ],
+            bin_sizes(&result.bins)
+        );
+        assert!(result.leftover.is_empty());
+        assert_eq!(1, result.bins[0].overflow());
+    }
+
+    #[test]
+    fn returns_leftovers() {
+        let result = next_fit(10, items(&[1, 1, 1, 1, 9, 9, 9, 9]));
+
+        assert_eq!(
+            vec![vec![1, 1, 1, 1, 9], vec![9, 9]],
+            bin_sizes(&result.bins)
+        );
+        assert_eq!(items(&[9]), result.leftover);
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let result = next_fit_by_key(1.0, vec![0.5, 0.25, 0.5, 0.75], |&size| size);
+
+        assert_eq!(&[0.5, 0.25, 0.5], result.bins[0].contents());
+        assert_eq!(vec![0.75], result.leftover);
+    }
+}
@@ -0,0 +1,90 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+
+use crate::{PackOptions, Size};
+
+/// Error returned when items cannot be packed.
+///
+/// The `try_` variants of the offline algorithms hand back a `Vec` of items.
+/// Errors of the online packers, which hold the single item that couldn't be added,
+/// convert into this type, see [`OnlinePackerError`](crate::online::online_packer::OnlinePackerError).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PackError<T> {
+    /// The bins have a size of zero, or can't hold any items, so nothing can be packed in them.
+    ZeroCapacity,
+    /// The item is too big to fit into any bin:
+    /// for example, the bins are size 10 and you're trying to pack an item of size 50.
+    ///
+    /// The `try_` variants of the offline algorithms hand back all items, not just the ones that are too large,
+    /// so that nothing is lost.
+    ItemTooLarge(T),
+    /// The total size of the items can't be represented by the size type.
+    Overflow,
+    /// There aren't enough bins available to hold all items,
+    /// see [`BinType::limit`](crate::offline::variable_sized::BinType::limit).
+    NotEnoughBins(T),
+}
+
+impl<T> PackError<T> {
+    /// Transform the items that are handed back into a different type.
+    pub fn map<U>(self, transform_fn: impl FnOnce(T) -> U) -> PackError<U> {
+        match self {
+            PackError::ZeroCapacity => PackError::ZeroCapacity,
+            PackError::ItemTooLarge(item) => PackError::ItemTooLarge(transform_fn(item)),
+            PackError::Overflow => PackError::Overflow,
+            PackError::NotEnoughBins(items) => PackError::NotEnoughBins(transform_fn(items)),
+        }
+    }
+}
+
+impl<T> Display for PackError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackError::ZeroCapacity => write!(f, "bin size must be greater than 0"),
+            PackError::ItemTooLarge(_) => write!(f, "item is too large to fit in any bin"),
+            PackError::Overflow => write!(f, "total size of the items overflows"),
+            PackError::NotEnoughBins(_) => write!(f, "not enough bins available"),
+        }
+    }
+}
+
+impl<T> Error for PackError<T> where T: Debug {}
+
+/// Check that items can be packed in bins of the given size.
+///
+/// Hands back all items, in the order they were given, if any of them are too large.
+#[doc(hidden)]
+pub(crate) fn __internal_check_items<T, S>(
+    bin_size: S,
+    items: Vec<T>,
+    size_fn: impl Fn(&T) -> S,
+) -> Result<Vec<T>, PackError<Vec<T>>>
+where
+    S: Size,
+{
+    if bin_size.partial_cmp(&S::ZERO) != Some(Ordering::Greater) {
+        return Err(PackError::ZeroCapacity);
+    }
+
+    if items.iter().any(|item| size_fn(item) > bin_size) {
+        return Err(PackError::ItemTooLarge(items));
+    }
+
+    items
+        .iter()
+        .try_fold(S::ZERO, |total, item| total.checked_add(size_fn(item)))
+        .ok_or(PackError::Overflow)?;
+
+    Ok(items)
+}
+
+/// Check that bins can hold any items with the given options.
+#[doc(hidden)]
+pub(crate) fn __internal_check_options<T>(options: PackOptions) -> Result<(), PackError<T>> {
+    if options.max_items == Some(0) {
+        return Err(PackError::ZeroCapacity);
+    }
+
+    Ok(())
+}
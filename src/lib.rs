@@ -40,80 +40,205 @@
 //! }
 //! ```
 
+pub mod bounds;
+pub mod class_constrained;
+pub mod conflicts;
+pub mod covering;
+mod error;
 pub mod offline;
 pub mod online;
+mod options;
+pub mod oversize;
+mod packing;
+pub mod partitioning;
+mod size;
+pub mod split;
+pub mod three_d;
+mod tournament_tree;
+pub mod two_d;
+pub mod vector;
 pub mod wrapper;
 
+pub use error::PackError;
+pub use options::PackOptions;
+pub use packing::{OverCapacity, Packing};
+pub use size::Size;
+
 /// Allows the bin packing algorithm to know how big an item is, which can then be used to
 /// figure out in which bin it fits.
-pub trait Pack {
+///
+/// The size is a `usize` by default, but any [`Size`] can be used instead,
+/// for example `u64` byte counts or `f64` durations.
+pub trait Pack<S = usize> {
     /// Get the size of the item to pack in bins.
-    fn size(&self) -> usize;
+    fn size(&self) -> S;
 }
 
+/// A bin holding the items that were packed in it.
+///
+/// For most algorithms the size of a bin is a single [`Size`] (a `usize` by default),
+/// but the [`vector`] packing algorithms use a bin with a size in every dimension.
 #[derive(Debug)]
 #[cfg_attr(test, derive(Eq, PartialEq))]
-pub struct Bin<T> {
+pub struct Bin<T, S = usize> {
     contents: Vec<T>,
-    remaining_capacity: usize,
+    capacity: S,
+    remaining_capacity: S,
+    overflow: S,
+    bin_type: Option<usize>,
 }
 
-impl<T> Bin<T> {
+impl<T, S> Bin<T, S>
+where
+    S: Size,
+{
     /// Create a new empty bin.
     #[doc(hidden)]
-    pub(crate) const fn with_capacity(capacity: usize) -> Self {
+    pub(crate) const fn with_capacity(capacity: S) -> Self {
         Self {
             contents: vec![],
+            capacity,
             remaining_capacity: capacity,
+            overflow: S::ZERO,
+            bin_type: None,
         }
     }
 
     /// Create a new bin with a single item.
     #[doc(hidden)]
-    pub(crate) fn with_item(capacity: usize, item: T) -> Self
+    pub(crate) fn with_item(capacity: S, item: T) -> Self
     where
-        T: Pack,
+        T: Pack<S>,
     {
-        Self {
-            remaining_capacity: capacity.saturating_sub(item.size()),
-            contents: vec![item],
-        }
+        let size = item.size();
+        Self::with_item_and_size(capacity, item, size)
     }
 
     /// Create a new bin with a single item, given its size.
     #[doc(hidden)]
-    pub(crate) fn with_item_and_size(capacity: usize, item: T, size: usize) -> Self {
-        Self {
-            remaining_capacity: capacity.saturating_sub(size),
-            contents: vec![item],
-        }
+    pub(crate) fn with_item_and_size(capacity: S, item: T, size: S) -> Self {
+        let mut bin = Self::with_capacity(capacity);
+        bin.add_with_size(item, size);
+        bin
     }
 
     /// Add an item to this bin, and update the remaining capacity.
     ///
-    /// Uses saturating subtraction: if you push a too-big item,
-    /// then the bin will have a remaining capacity of zero.
+    /// If you push a too-big item, then the bin will have a remaining capacity of zero,
+    /// and the part that didn't fit is recorded as [`Bin::overflow`].
     #[doc(hidden)]
     pub(crate) fn add(&mut self, item: T)
     where
-        T: Pack,
+        T: Pack<S>,
     {
-        self.remaining_capacity = self.remaining_capacity.saturating_sub(item.size());
-        self.contents.push(item);
+        let size = item.size();
+        self.add_with_size(item, size);
     }
 
     /// Add an item to this bin (given its size) and update the remaining capacity.
     #[doc(hidden)]
-    pub(crate) fn add_with_size(&mut self, item: T, size: usize) {
-        self.remaining_capacity = self.remaining_capacity.saturating_sub(size);
+    pub(crate) fn add_with_size(&mut self, item: T, size: S) {
+        if size > self.remaining_capacity {
+            self.overflow = self.overflow + (size - self.remaining_capacity);
+            self.remaining_capacity = S::ZERO;
+        } else {
+            self.remaining_capacity = self.remaining_capacity - size;
+        }
+
         self.contents.push(item);
     }
 
+    /// Remove the item at `index` from this bin (given its size) and update the remaining capacity.
+    #[doc(hidden)]
+    pub(crate) fn remove_with_size(&mut self, index: usize, size: S) -> T {
+        if size > self.overflow {
+            self.remaining_capacity = self.remaining_capacity + (size - self.overflow);
+            self.overflow = S::ZERO;
+        } else {
+            self.overflow = self.overflow - size;
+        }
+
+        self.contents.remove(index)
+    }
+
+    /// Check whether the contents of this bin exceed its capacity.
+    ///
+    /// A bin that is exactly full is not overflowing.
+    pub fn is_overflowing(&self) -> bool {
+        self.overflow > S::ZERO
+    }
+
+    /// Get the total size of the contents of this bin.
+    ///
+    /// This includes the [`Bin::overflow`], so it can be larger than the capacity.
+    pub fn used(&self) -> S {
+        self.capacity - self.remaining_capacity + self.overflow
+    }
+
+    /// Get the part of the capacity of this bin that is used, between 0 and 1
+    /// (or more if the bin is overflowing).
+    pub fn fill_ratio(&self) -> f64 {
+        self.used().to_f64() / self.capacity.to_f64()
+    }
+}
+
+impl<T, S> Bin<T, S> {
+    /// Get the type of this bin, as an index into the catalogue of bin types
+    /// it was packed with (see [`crate::offline::variable_sized`]).
+    ///
+    /// Algorithms that only use a single bin size return `None`.
+    pub fn bin_type(&self) -> Option<usize> {
+        self.bin_type
+    }
+
+    /// Get how much fits in this bin.
+    pub fn capacity(&self) -> S
+    where
+        S: Clone,
+    {
+        self.capacity.clone()
+    }
+
+    /// Get how much still fits in this bin.
+    pub fn remaining_capacity(&self) -> S
+    where
+        S: Clone,
+    {
+        self.remaining_capacity.clone()
+    }
+
+    /// Get how much the contents of this bin exceed its capacity.
+    ///
+    /// This is zero unless an item that is larger than the bin was put in it,
+    /// see [`OversizePolicy::OwnBin`](crate::oversize::OversizePolicy::OwnBin).
+    /// [`vector`] bins record the overflow in every dimension.
+    pub fn overflow(&self) -> S
+    where
+        S: Clone,
+    {
+        self.overflow.clone()
+    }
+
     /// Get the contents of the bin.
     pub fn contents(&self) -> &[T] {
         &self.contents
     }
 
+    /// Get the number of items in the bin.
+    pub fn len(&self) -> usize {
+        self.contents.len()
+    }
+
+    /// Check whether the bin holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.contents.is_empty()
+    }
+
+    /// Iterate over the contents of the bin.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.contents.iter()
+    }
+
     /// Get the contents of the bin.
     pub fn into_contents(self) -> Vec<T> {
         self.contents
@@ -127,14 +252,35 @@ impl<T> Bin<T> {
     /// that is allowed here.
     /// In fact, the new type doesn't even have to implement [`crate::Pack`],
     /// so it might not even have a reasonable notion of size.
-    pub fn map<U>(self, transform_fn: impl Fn(T) -> U) -> Bin<U> {
+    pub fn map<U>(self, transform_fn: impl Fn(T) -> U) -> Bin<U, S> {
         Bin {
             contents: self.contents.into_iter().map(transform_fn).collect(),
+            capacity: self.capacity,
             remaining_capacity: self.remaining_capacity,
+            overflow: self.overflow,
+            bin_type: self.bin_type,
         }
     }
 }
 
+impl<T, S> IntoIterator for Bin<T, S> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.contents.into_iter()
+    }
+}
+
+impl<'a, T, S> IntoIterator for &'a Bin<T, S> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.contents.iter()
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -203,6 +349,17 @@ pub mod tests {
         )
     }
 
+    /// Pseudo-random numbers below `max`, starting from `seed`.
+    ///
+    /// This is a simple linear congruential generator, so the test data is deterministic.
+    pub fn random_numbers(seed: usize, max: usize) -> impl Iterator<Item = usize> {
+        let mut state = seed;
+        std::iter::repeat_with(move || {
+            state = (state * 1103515245 + 12345) % (1 << 31);
+            state % max
+        })
+    }
+
     pub fn generate_test_bins(bin_size: usize, data: Vec<Vec<usize>>) -> Vec<Bin<MyItem>> {
         data.into_iter()
             .map(|bin_data| expected_test_bin(bin_size, bin_data))
@@ -210,9 +367,44 @@ pub mod tests {
     }
 
     pub fn expected_test_bin(bin_size: usize, data: Vec<usize>) -> Bin<MyItem> {
+        let used = data.iter().sum::<usize>();
+
         Bin {
             contents: data.iter().map(|i| MyItem { size: *i }).collect::<Vec<_>>(),
-            remaining_capacity: bin_size - data.iter().sum::<usize>(),
+            capacity: bin_size,
+            remaining_capacity: bin_size.saturating_sub(used),
+            overflow: used.saturating_sub(bin_size),
+            bin_type: None,
         }
     }
+
+    #[test]
+    fn bin_introspection() {
+        let mut bin = Bin::with_capacity(20);
+        assert!(bin.is_empty());
+        assert_eq!(0.0, bin.fill_ratio());
+
+        bin.add(MyItem { size: 10 });
+        bin.add(MyItem { size: 5 });
+
+        assert_eq!(20, bin.capacity());
+        assert_eq!(5, bin.remaining_capacity());
+        assert_eq!(15, bin.used());
+        assert_eq!(0.75, bin.fill_ratio());
+        assert_eq!(2, bin.len());
+        assert_eq!(
+            vec![10, 5],
+            bin.iter().map(|item| item.size).collect::<Vec<_>>()
+        );
+
+        bin.add(MyItem { size: 15 });
+
+        assert_eq!(0, bin.remaining_capacity());
+        assert_eq!(30, bin.used());
+        assert_eq!(1.5, bin.fill_ratio());
+        assert_eq!(
+            vec![10, 5, 15],
+            bin.into_iter().map(|item| item.size).collect::<Vec<_>>()
+        );
+    }
 }
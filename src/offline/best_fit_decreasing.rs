@@ -0,0 +1,239 @@
+use crate::bounds::l1;
+use crate::error::{__internal_check_items, __internal_check_options};
+use crate::options::assert_valid_options;
+use crate::wrapper::SizedWrapper;
+use crate::{Bin, Pack, PackError, PackOptions, Size};
+
+/// Pack items in bins using the [Best-fit-decreasing](https://en.wikipedia.org/wiki/Best-fit_bin_packing)
+/// bin packing algorithm, with the constraints in `options`.
+///
+/// Panics if `bin_size` is 0, or if the options leave no room for any items.
+pub fn best_fit_decreasing<T, S>(
+    bin_size: S,
+    mut items: Vec<T>,
+    options: PackOptions,
+) -> Vec<Bin<T, S>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    assert!(bin_size > S::ZERO, "Bin size must be greater than 0");
+    assert_valid_options(options);
+
+    // Sort the items in decreasing order
+    items.sort_unstable_by(|a, b| b.size().total_cmp(&a.size()));
+
+    let lower_bound = l1(bin_size, &items).max(
+        options
+            .max_items
+            .map_or(0, |max_items| items.len().div_ceil(max_items)),
+    );
+
+    __internal_best_fit(bin_size, items, lower_bound, options.max_items)
+}
+
+/// Pack items in bins using the [Best-fit-decreasing](https://en.wikipedia.org/wiki/Best-fit_bin_packing)
+/// bin packing algorithm, with the constraints in `options`.
+///
+/// Unlike [`best_fit_decreasing`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+///
+/// This function will be cloned for each item
+/// (but if it's a simple function pointer or a non-capturing closure, then it is a no-op).
+pub fn best_fit_decreasing_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: Vec<T>,
+    options: PackOptions,
+    key_func: SizeFunc,
+) -> Vec<Bin<T, S>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S + Clone,
+{
+    let items = items
+        .into_iter()
+        .map(|item| SizedWrapper::new_generic(key_func.clone(), item))
+        .collect();
+
+    best_fit_decreasing(bin_size, items, options)
+        .into_iter()
+        .map(|bin| bin.map(|item| item.take()))
+        .collect()
+}
+
+/// Pack items in bins using the [Best-fit-decreasing](https://en.wikipedia.org/wiki/Best-fit_bin_packing)
+/// bin packing algorithm, with the constraints in `options`.
+///
+/// Unlike [`best_fit_decreasing`], this doesn't panic or put items that are too large in their own bin,
+/// but returns a [`PackError`] instead.
+/// If any items are too large, all items are handed back in [`PackError::ItemTooLarge`],
+/// in the order they were given.
+pub fn try_best_fit_decreasing<T, S>(
+    bin_size: S,
+    items: Vec<T>,
+    options: PackOptions,
+) -> Result<Vec<Bin<T, S>>, PackError<Vec<T>>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    __internal_check_options(options)?;
+    let items = __internal_check_items(bin_size, items, T::size)?;
+
+    Ok(best_fit_decreasing(bin_size, items, options))
+}
+
+/// Pack items in bins using the [Best-fit-decreasing](https://en.wikipedia.org/wiki/Best-fit_bin_packing)
+/// bin packing algorithm, with the constraints in `options`.
+///
+/// Like [`try_best_fit_decreasing`], but the size of the items is determined by `key_func`,
+/// see [`best_fit_decreasing_by_key`].
+pub fn try_best_fit_decreasing_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: Vec<T>,
+    options: PackOptions,
+    key_func: SizeFunc,
+) -> Result<Vec<Bin<T, S>>, PackError<Vec<T>>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S + Clone,
+{
+    __internal_check_options(options)?;
+    let items = __internal_check_items(bin_size, items, &key_func)?;
+
+    Ok(best_fit_decreasing_by_key(
+        bin_size, items, options, key_func,
+    ))
+}
+
+#[doc(hidden)]
+pub(crate) fn __internal_best_fit<T, S>(
+    bin_size: S,
+    items: impl IntoIterator<Item = T>,
+    lower_bound: usize,
+    max_items: Option<usize>,
+) -> Vec<Bin<T, S>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    let mut bins = Vec::<Bin<T, S>>::with_capacity(lower_bound);
+
+    for item in items.into_iter() {
+        let item_size = item.size();
+
+        // Find the bin with the least remaining capacity that the item still fits in
+        match bins
+            .iter_mut()
+            .filter(|bin| item_size <= bin.remaining_capacity)
+            .filter(|bin| max_items.is_none_or(|max_items| bin.len() < max_items))
+            .min_by(|a, b| a.remaining_capacity.total_cmp(&b.remaining_capacity))
+        {
+            Some(bin) => bin.add_with_size(item, item_size),
+            None => bins.push(Bin::with_item_and_size(bin_size, item, item_size)),
+        }
+    }
+
+    bins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{generate_test_bins, generate_test_set_a, MyItem};
+
+    #[test]
+    fn it_works() {
+        let (test_data, bin_size) = generate_test_set_a();
+
+        let result = best_fit_decreasing(bin_size, test_data, PackOptions::default());
+
+        // Best fit decreasing would result in the optimal solution
+
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![19, 1],          // 20
+                vec![19, 1],          // 20
+                vec![10, 10],         // 20
+                vec![10, 4, 3, 1, 1], //19
+            ],
+        );
+
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let (test_data, bin_size) = generate_test_set_a();
+
+        let test_data = test_data
+            .into_iter()
+            .map(|item| item.make_unpacked())
+            .collect::<Vec<_>>();
+
+        let result =
+            best_fit_decreasing_by_key(bin_size, test_data, PackOptions::default(), |item| {
+                item.size
+            });
+
+        // Best fit decreasing by key would result in the optimal solution
+
+        let expected: Vec<_> = generate_test_bins(
+            20,
+            vec![
+                vec![19, 1],          // 20
+                vec![19, 1],          // 20
+                vec![10, 10],         // 20
+                vec![10, 4, 3, 1, 1], //19
+            ],
+        )
+        .into_iter()
+        .map(|bin| bin.map(|item| item.make_unpacked()))
+        .collect();
+
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn prefers_tightest_bin_over_first_bin() {
+        let test_data = [7, 4, 4, 2]
+            .into_iter()
+            .map(|size| MyItem { size })
+            .collect::<Vec<_>>();
+
+        let result = best_fit_decreasing(10, test_data, PackOptions::default());
+
+        // First fit decreasing would put the 2 next to the 7,
+        // but the bin holding both 4s has less room left.
+        let expected = generate_test_bins(
+            10,
+            vec![
+                vec![7],       // 7
+                vec![4, 4, 2], // 10
+            ],
+        );
+
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn respects_max_items() {
+        let (test_data, bin_size) = generate_test_set_a();
+
+        let result = best_fit_decreasing(bin_size, test_data, PackOptions::new().with_max_items(3));
+
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![19, 1],    // 20
+                vec![19, 1],    // 20
+                vec![10, 10],   // 20
+                vec![10, 4, 3], // 17
+                vec![1, 1],     // 2
+            ],
+        );
+
+        assert_eq!(expected, result)
+    }
+}
@@ -0,0 +1,643 @@
+use std::time::{Duration, Instant};
+
+use crate::bounds::{__internal_l2, l1_by_key};
+use crate::error::{__internal_check_items, __internal_check_options};
+use crate::offline::first_fit_decreasing::first_fit_decreasing_with_options_by_key;
+use crate::options::assert_valid_options;
+use crate::wrapper::SizedWrapper;
+use crate::{Bin, Pack, PackError, PackOptions, Size};
+
+/// Limits how much work [`branch_and_bound`] is allowed to do.
+///
+/// Once either limit is reached, the search stops and the best solution found so far is returned.
+/// The default budget is unlimited.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Budget {
+    /// The maximum number of nodes of the search tree to visit.
+    pub max_nodes: Option<usize>,
+    /// The maximum amount of time to spend searching.
+    pub max_duration: Option<Duration>,
+}
+
+impl Budget {
+    /// A budget without any limits: the search will always run until optimality is proven.
+    pub const fn unlimited() -> Self {
+        Self {
+            max_nodes: None,
+            max_duration: None,
+        }
+    }
+
+    /// Limit the search to the given number of nodes.
+    pub const fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Limit the search to the given amount of time.
+    pub const fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+}
+
+/// The result of [`branch_and_bound`].
+#[derive(Debug)]
+pub struct ExactSolution<T, S = usize> {
+    /// The best packing that was found.
+    pub bins: Vec<Bin<T, S>>,
+    /// Whether `bins` is proven to use the minimum number of bins.
+    ///
+    /// This is only `false` if the [`Budget`] ran out before the search could finish.
+    pub optimal: bool,
+}
+
+/// Pack items in the minimum number of bins, using a depth-first branch-and-bound search
+/// in the style of the MTP procedure by Martello and Toth, with the constraints in `options`.
+///
+/// The search starts from the [First-fit-decreasing](crate::offline::first_fit_decreasing)
+/// solution, and prunes any branch that can't beat the best solution found so far
+/// according to the [`l1`](crate::bounds::l1) and [`l2`](crate::bounds::l2) lower bounds.
+///
+/// The worst case running time is exponential, so the `budget` can be used to cap the search;
+/// check [`ExactSolution::optimal`] to see whether the search was able to finish.
+///
+/// Items larger than `bin_size` are each put in their own bin.
+///
+/// Panics if `bin_size` is 0, or if the options leave no room for any items.
+pub fn branch_and_bound<T, S>(
+    bin_size: S,
+    mut items: Vec<T>,
+    budget: Budget,
+    options: PackOptions,
+) -> ExactSolution<T, S>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    assert!(bin_size > S::ZERO, "Bin size must be greater than 0");
+    assert_valid_options(options);
+
+    // Sort the items in decreasing order
+    items.sort_unstable_by(|a, b| b.size().total_cmp(&a.size()));
+
+    let sizes = items.iter().map(|item| item.size()).collect::<Vec<_>>();
+    let (assignment, optimal) = solve(bin_size, &sizes, budget, options);
+
+    let mut bins: Vec<Bin<T, S>> = Vec::new();
+    for (item, bin_idx) in items.into_iter().zip(assignment) {
+        match bins.get_mut(bin_idx) {
+            Some(bin) => bin.add(item),
+            None => bins.push(Bin::with_item(bin_size, item)),
+        }
+    }
+
+    ExactSolution { bins, optimal }
+}
+
+/// Pack items in the minimum number of bins, using a depth-first branch-and-bound search
+/// in the style of the MTP procedure by Martello and Toth, with the constraints in `options`.
+///
+/// Unlike [`branch_and_bound`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+///
+/// This function will be cloned for each item
+/// (but if it's a simple function pointer or a non-capturing closure, then it is a no-op).
+pub fn branch_and_bound_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: Vec<T>,
+    budget: Budget,
+    options: PackOptions,
+    key_func: SizeFunc,
+) -> ExactSolution<T, S>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S + Clone,
+{
+    let items = items
+        .into_iter()
+        .map(|item| SizedWrapper::new_generic(key_func.clone(), item))
+        .collect();
+
+    let solution = branch_and_bound(bin_size, items, budget, options);
+
+    ExactSolution {
+        bins: solution
+            .bins
+            .into_iter()
+            .map(|bin| bin.map(|item| item.take()))
+            .collect(),
+        optimal: solution.optimal,
+    }
+}
+
+/// Pack items in the minimum number of bins, using a depth-first branch-and-bound search
+/// in the style of the MTP procedure by Martello and Toth, with the constraints in `options`.
+///
+/// Unlike [`branch_and_bound`], this doesn't panic or put items that are too large in their own bin,
+/// but returns a [`PackError`] instead.
+/// If any items are too large, all items are handed back in [`PackError::ItemTooLarge`],
+/// in the order they were given.
+pub fn try_branch_and_bound<T, S>(
+    bin_size: S,
+    items: Vec<T>,
+    budget: Budget,
+    options: PackOptions,
+) -> Result<ExactSolution<T, S>, PackError<Vec<T>>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    __internal_check_options(options)?;
+    let items = __internal_check_items(bin_size, items, T::size)?;
+
+    Ok(branch_and_bound(bin_size, items, budget, options))
+}
+
+/// Pack items in the minimum number of bins, using a depth-first branch-and-bound search
+/// in the style of the MTP procedure by Martello and Toth, with the constraints in `options`.
+///
+/// Like [`try_branch_and_bound`], but the size of the items is determined by `key_func`,
+/// see [`branch_and_bound_by_key`].
+pub fn try_branch_and_bound_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: Vec<T>,
+    budget: Budget,
+    options: PackOptions,
+    key_func: SizeFunc,
+) -> Result<ExactSolution<T, S>, PackError<Vec<T>>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S + Clone,
+{
+    __internal_check_options(options)?;
+    let items = __internal_check_items(bin_size, items, &key_func)?;
+
+    Ok(branch_and_bound_by_key(
+        bin_size, items, budget, options, key_func,
+    ))
+}
+
+/// Find the best assignment of the (decreasingly sorted) sizes to bins.
+///
+/// Returns the bin index for every size, where bins are numbered in order of first use,
+/// and whether the assignment is proven to be optimal.
+fn solve<S>(bin_size: S, sizes: &[S], budget: Budget, options: PackOptions) -> (Vec<usize>, bool)
+where
+    S: Size,
+{
+    // Items that don't fit in any bin get a bin of their own,
+    // since they are sorted first these simply take up the first bins.
+    let oversized = sizes.iter().take_while(|&&size| size > bin_size).count();
+    let sizes = &sizes[oversized..];
+
+    let mut solver = Solver::new(bin_size, sizes, budget, options);
+    solver.run();
+
+    let assignment = (0..oversized)
+        .chain(solver.best.into_iter().map(|bin_idx| bin_idx + oversized))
+        .collect();
+
+    (
+        assignment,
+        !solver.exhausted || solver.best_count == solver.lower_bound,
+    )
+}
+
+struct Solver<'a, S> {
+    bin_size: S,
+    sizes: &'a [S],
+    total_size: S,
+    max_items: Option<usize>,
+
+    /// The remaining capacity of every bin that is open in the current branch.
+    residuals: Vec<S>,
+    /// The number of items in every bin that is open in the current branch.
+    counts: Vec<usize>,
+    /// The bin every item is assigned to in the current branch.
+    assignment: Vec<usize>,
+
+    best: Vec<usize>,
+    best_count: usize,
+    lower_bound: usize,
+
+    budget: Budget,
+    started: Instant,
+    nodes: usize,
+    exhausted: bool,
+}
+
+impl<'a, S> Solver<'a, S>
+where
+    S: Size,
+{
+    fn new(bin_size: S, sizes: &'a [S], budget: Budget, options: PackOptions) -> Self {
+        // The first-fit-decreasing solution is the initial upper bound
+        let indices = (0..sizes.len()).collect();
+        let ffd =
+            first_fit_decreasing_with_options_by_key(bin_size, indices, options, |&idx| sizes[idx]);
+        let max_items = options.max_items;
+
+        let mut best = vec![0; sizes.len()];
+        let mut best_count = 0;
+        for bin in ffd.iter().filter(|bin| !bin.contents().is_empty()) {
+            for &idx in bin.contents() {
+                best[idx] = best_count;
+            }
+            best_count += 1;
+        }
+
+        // The search assumes bins are numbered in order of first use
+        let best = renumber(&best);
+
+        Self {
+            bin_size,
+            sizes,
+            total_size: sizes.iter().fold(S::ZERO, |total, &size| total + size),
+            max_items,
+            residuals: Vec::new(),
+            counts: Vec::new(),
+            assignment: Vec::with_capacity(sizes.len()),
+            best,
+            best_count,
+            lower_bound: l1_by_key(bin_size, sizes, |&size| size)
+                .max(__internal_l2(bin_size, sizes))
+                .max(max_items.map_or(0, |max_items| sizes.len().div_ceil(max_items))),
+            budget,
+            started: Instant::now(),
+            nodes: 0,
+            exhausted: false,
+        }
+    }
+
+    fn run(&mut self) {
+        if self.best_count <= self.lower_bound {
+            return;
+        }
+
+        // The search keeps its own stack, since the depth of the search tree is the number of items
+        let mut stack = Vec::from_iter(self.enter(0));
+
+        while let Some(frame) = stack.last_mut() {
+            if let Some((bin_idx, residual)) = frame.placed.take() {
+                self.remove(bin_idx, residual);
+                if bin_idx == frame.open {
+                    self.residuals.pop();
+                    self.counts.pop();
+                }
+            }
+
+            if self.exhausted || self.best_count == self.lower_bound {
+                return;
+            }
+
+            let Some(bin_idx) = self.next_bin(frame) else {
+                stack.pop();
+                continue;
+            };
+
+            if bin_idx == frame.open {
+                self.residuals.push(self.bin_size);
+                self.counts.push(0);
+            }
+            frame.placed = Some((bin_idx, self.residuals[bin_idx]));
+            self.place(frame.idx, bin_idx);
+
+            let child = self.enter(frame.idx + 1);
+            stack.extend(child);
+        }
+    }
+
+    /// Visit the node of the search tree where the items before `idx` have been placed.
+    ///
+    /// Returns the frame to search its branches with, or `None` if there is nothing left to search.
+    fn enter(&mut self, idx: usize) -> Option<Frame<S>> {
+        if self.exhausted || self.best_count == self.lower_bound {
+            return None;
+        }
+
+        self.nodes += 1;
+        if self.budget_exceeded() {
+            self.exhausted = true;
+            return None;
+        }
+
+        if idx == self.sizes.len() {
+            if self.residuals.len() < self.best_count {
+                self.best_count = self.residuals.len();
+                self.best.clone_from(&self.assignment);
+            }
+            return None;
+        }
+
+        if self.node_lower_bound(idx) >= self.best_count {
+            return None;
+        }
+
+        let size = self.sizes[idx];
+        let open = self.residuals.len();
+
+        // Putting an item in a bin it fills up exactly is never worse than any other choice,
+        // unless the number of items is limited: the items it replaces might need more room
+        let exact = self
+            .max_items
+            .is_none()
+            .then(|| self.residuals.iter().position(|&residual| residual == size))
+            .flatten();
+
+        let (next_bin, end) = match exact {
+            Some(bin_idx) => (bin_idx, bin_idx + 1),
+            None => (0, open + 1),
+        };
+
+        Some(Frame {
+            idx,
+            next_bin,
+            end,
+            open,
+            tried: Vec::new(),
+            placed: None,
+        })
+    }
+
+    /// Find the next bin to put the item of the frame in, if any.
+    fn next_bin(&self, frame: &mut Frame<S>) -> Option<usize> {
+        let size = self.sizes[frame.idx];
+
+        while frame.next_bin < frame.end {
+            let bin_idx = frame.next_bin;
+            frame.next_bin += 1;
+
+            if bin_idx == frame.open {
+                return (frame.open + 1 < self.best_count).then_some(bin_idx);
+            }
+
+            // Bins with the same remaining capacity and number of items lead to equivalent branches,
+            // so only try one
+            let key = (self.residuals[bin_idx], self.counts[bin_idx]);
+            if size <= key.0 && self.has_room(bin_idx) && !frame.tried.contains(&key) {
+                frame.tried.push(key);
+                return Some(bin_idx);
+            }
+        }
+
+        None
+    }
+
+    /// Check whether another item can be put in the bin, as far as the number of items is concerned.
+    fn has_room(&self, bin_idx: usize) -> bool {
+        self.max_items
+            .is_none_or(|max_items| self.counts[bin_idx] < max_items)
+    }
+
+    /// Put the item at `idx` in the given bin.
+    fn place(&mut self, idx: usize, bin_idx: usize) {
+        self.residuals[bin_idx] = self.residuals[bin_idx] - self.sizes[idx];
+        self.counts[bin_idx] += 1;
+        self.assignment.push(bin_idx);
+    }
+
+    /// Take the last placed item out of the given bin again, restoring its remaining capacity.
+    fn remove(&mut self, bin_idx: usize, residual: S) {
+        self.assignment.pop();
+        self.counts[bin_idx] -= 1;
+        self.residuals[bin_idx] = residual;
+    }
+
+    /// A lower bound on the number of bins needed to complete the current branch,
+    /// where the items before `idx` have been placed.
+    ///
+    /// Any remaining capacity that is smaller than every item that is left,
+    /// or that is in a bin that can't hold any more items, is wasted,
+    /// so the items that are left have to fit in the rest of the space.
+    /// In the same way, they have to fit in the number of items that the bins can still hold.
+    fn node_lower_bound(&self, idx: usize) -> usize {
+        let smallest = *self.sizes.last().unwrap_or(&S::ZERO);
+        let wasted = self
+            .residuals
+            .iter()
+            .enumerate()
+            .filter(|&(bin_idx, &residual)| residual < smallest || !self.has_room(bin_idx))
+            .fold(S::ZERO, |wasted, (_, &residual)| wasted + residual);
+
+        let by_size = (self.total_size + wasted).div_ceil(self.bin_size);
+        let by_count = self.max_items.map_or(0, |max_items| {
+            let free = self
+                .counts
+                .iter()
+                .map(|&count| max_items - count)
+                .sum::<usize>();
+            let left = self.sizes.len() - idx;
+            self.residuals.len() + left.saturating_sub(free).div_ceil(max_items)
+        });
+
+        self.residuals.len().max(by_size).max(by_count)
+    }
+
+    fn budget_exceeded(&self) -> bool {
+        if let Some(max_nodes) = self.budget.max_nodes {
+            if self.nodes > max_nodes {
+                return true;
+            }
+        }
+
+        // Checking the time is relatively expensive, so only do so every 1024 nodes
+        if let Some(max_duration) = self.budget.max_duration {
+            if self.nodes & 1023 == 0 && self.started.elapsed() >= max_duration {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// A node of the search tree, where the item at `idx` is being placed.
+struct Frame<S> {
+    idx: usize,
+    /// The next bin to try putting the item in, where `open` stands for a new bin.
+    next_bin: usize,
+    /// Only the bins before `end` are tried.
+    end: usize,
+    /// The number of bins that were open when the node was visited.
+    open: usize,
+    /// The remaining capacity and number of items of every bin that was tried.
+    tried: Vec<(S, usize)>,
+    /// The bin the item is in for the branch that is being searched, and its remaining capacity before.
+    placed: Option<(usize, S)>,
+}
+
+/// Renumber bins in the order they are first used in the assignment.
+fn renumber(assignment: &[usize]) -> Vec<usize> {
+    let mut mapping: Vec<Option<usize>> = vec![None; assignment.len()];
+    let mut next = 0;
+
+    assignment
+        .iter()
+        .map(|&bin_idx| {
+            *mapping[bin_idx].get_or_insert_with(|| {
+                next += 1;
+                next - 1
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{generate_test_bins, generate_test_set_a, MyItem};
+
+    fn items(sizes: &[usize]) -> Vec<MyItem> {
+        sizes.iter().map(|&size| MyItem { size }).collect()
+    }
+
+    #[test]
+    fn it_works() {
+        let (test_data, bin_size) = generate_test_set_a();
+
+        let result = branch_and_bound(
+            bin_size,
+            test_data,
+            Budget::unlimited(),
+            PackOptions::default(),
+        );
+
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![19, 1],          // 20
+                vec![19, 1],          // 20
+                vec![10, 10],         // 20
+                vec![10, 4, 3, 1, 1], //19
+            ],
+        );
+
+        assert!(result.optimal);
+        assert_eq!(expected, result.bins)
+    }
+
+    #[test]
+    fn improves_on_first_fit_decreasing() {
+        let test_data = items(&[3, 4, 5, 2, 2, 4]);
+
+        // First fit decreasing needs 3 bins: [5, 4], [4, 3, 2] and [2]
+        let result = branch_and_bound(10, test_data, Budget::unlimited(), PackOptions::default());
+
+        let expected = generate_test_bins(
+            10,
+            vec![
+                vec![5, 3, 2], // 10
+                vec![4, 4, 2], // 10
+            ],
+        );
+
+        assert!(result.optimal);
+        assert_eq!(expected, result.bins)
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let test_data = items(&[3, 4, 5, 2, 2, 4])
+            .into_iter()
+            .map(|item| item.make_unpacked())
+            .collect::<Vec<_>>();
+
+        let result = branch_and_bound_by_key(
+            10,
+            test_data,
+            Budget::unlimited(),
+            PackOptions::default(),
+            |item| item.size,
+        );
+
+        assert!(result.optimal);
+        assert_eq!(2, result.bins.len());
+    }
+
+    #[test]
+    fn exhausted_budget_returns_first_fit_decreasing() {
+        let test_data = items(&[3, 4, 5, 2, 2, 4]);
+
+        let result = branch_and_bound(
+            10,
+            test_data,
+            Budget::unlimited().with_max_nodes(0),
+            PackOptions::default(),
+        );
+
+        let expected = generate_test_bins(
+            10,
+            vec![
+                vec![5, 4],    // 9
+                vec![4, 3, 2], // 9
+                vec![2],       // 2
+            ],
+        );
+
+        assert!(!result.optimal);
+        assert_eq!(expected, result.bins)
+    }
+
+    #[test]
+    fn empty_input_returns_no_bins() {
+        let result =
+            branch_and_bound::<MyItem, _>(10, vec![], Budget::unlimited(), PackOptions::default());
+
+        assert!(result.optimal);
+        assert_eq!(result.bins, vec![]);
+    }
+
+    #[test]
+    fn large_input_does_not_overflow_the_stack() {
+        // First fit decreasing needs 3 bins for the large items,
+        // so the search has to go as deep as there are items to find the 2 bins that suffice
+        let mut sizes = vec![500_000usize, 400_000, 400_000, 300_000, 200_000, 200_000];
+        sizes.resize(sizes.len() + 100_000, 1);
+
+        let result = branch_and_bound_by_key(
+            1_050_000,
+            sizes,
+            Budget::unlimited(),
+            PackOptions::default(),
+            |&size| size,
+        );
+
+        assert!(result.optimal);
+        assert_eq!(2, result.bins.len());
+    }
+
+    #[test]
+    fn respects_max_items() {
+        let test_data = items(&[6, 3, 4, 9, 4, 3, 4, 9, 8]);
+
+        // First fit decreasing needs 4 bins: [9, 9], [8, 6, 4], [4, 4, 3] and [3]
+        let result = branch_and_bound(
+            20,
+            test_data,
+            Budget::unlimited(),
+            PackOptions::new().with_max_items(3),
+        );
+
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![9, 8, 3], // 20
+                vec![9, 6, 4], // 19
+                vec![4, 4, 3], // 11
+            ],
+        );
+
+        assert!(result.optimal);
+        assert_eq!(expected, result.bins);
+        assert!(matches!(
+            try_branch_and_bound(
+                20,
+                items(&[5]),
+                Budget::unlimited(),
+                PackOptions::new().with_max_items(0)
+            ),
+            Err(PackError::ZeroCapacity)
+        ));
+    }
+}
@@ -1,24 +1,18 @@
+use crate::bounds::l1;
+use crate::error::{__internal_check_items, __internal_check_options};
 use crate::online::first_fit::__internal_first_fit;
+use crate::options::assert_valid_options;
 use crate::wrapper::SizedWrapper;
-use crate::{Bin, Pack};
+use crate::{Bin, Pack, PackError, PackOptions, Size};
 
 /// Pack items in bins using the [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing)
 /// bin packing algorithm.
-pub fn first_fit_decreasing<T>(bin_size: usize, mut items: Vec<T>) -> Vec<Bin<T>>
+pub fn first_fit_decreasing<T, S>(bin_size: S, items: Vec<T>) -> Vec<Bin<T, S>>
 where
-    T: Pack,
+    T: Pack<S>,
+    S: Size,
 {
-    assert!(bin_size > 0, "Bin size must be greater than 0");
-
-    // Sort the items in decreasing order
-    items.sort_unstable_by(|a, b| b.size().cmp(&a.size()));
-
-    let lower_bound: usize = ((items.iter().map(|item| item.size()).sum::<usize>() as f64)
-        / (bin_size as f64))
-        .ceil() as usize;
-
-    // Use the normal first fit implementation
-    __internal_first_fit(bin_size, items, lower_bound)
+    first_fit_decreasing_with_options(bin_size, items, PackOptions::default())
 }
 
 /// Pack items in bins using the [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing)
@@ -29,40 +23,122 @@ where
 ///
 /// This function will be cloned for each item
 /// (but if it's a simple function pointer or a non-capturing closure, then it is a no-op).
-
-pub fn first_fit_decreasing_by_key<T, SizeFunc>(
-    bin_size: usize,
+pub fn first_fit_decreasing_by_key<T, S, SizeFunc>(
+    bin_size: S,
     items: Vec<T>,
     key_func: SizeFunc,
-) -> Vec<Bin<T>>
+) -> Vec<Bin<T, S>>
 where
-    SizeFunc: Fn(&T) -> usize + Clone,
+    S: Size,
+    SizeFunc: Fn(&T) -> S + Clone,
 {
-    assert!(bin_size > 0, "Bin size must be greater than 0");
+    first_fit_decreasing_with_options_by_key(bin_size, items, PackOptions::default(), key_func)
+}
 
-    // Sort the items in decreasing order
-    let mut items: Vec<_> = items
-        .into_iter()
-        .map(|item| SizedWrapper::new(key_func.clone(), item))
-        .collect();
+/// Pack items in bins using the [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing)
+/// bin packing algorithm, with the constraints in `options`.
+///
+/// Panics if `bin_size` is 0, or if the options leave no room for any items.
+pub fn first_fit_decreasing_with_options<T, S>(
+    bin_size: S,
+    mut items: Vec<T>,
+    options: PackOptions,
+) -> Vec<Bin<T, S>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    assert!(bin_size > S::ZERO, "Bin size must be greater than 0");
+    assert_valid_options(options);
 
-    items.sort_unstable_by(|a, b| b.size().cmp(&a.size()));
+    // Sort the items in decreasing order
+    items.sort_unstable_by(|a, b| b.size().total_cmp(&a.size()));
 
-    let lower_bound: usize = ((items.iter().map(|item| item.size()).sum::<usize>() as f64)
-        / (bin_size as f64))
-        .ceil() as usize;
+    let lower_bound = l1(bin_size, &items).max(
+        options
+            .max_items
+            .map_or(0, |max_items| items.len().div_ceil(max_items)),
+    );
 
     // Use the normal first fit implementation
-    __internal_first_fit(bin_size, items, lower_bound)
+    __internal_first_fit(bin_size, items, lower_bound, options.max_items)
+}
+
+/// Pack items in bins using the [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing)
+/// bin packing algorithm, with the constraints in `options`.
+///
+/// Like [`first_fit_decreasing_with_options`], but the size of the items is determined by `key_func`,
+/// see [`first_fit_decreasing_by_key`].
+pub fn first_fit_decreasing_with_options_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: Vec<T>,
+    options: PackOptions,
+    key_func: SizeFunc,
+) -> Vec<Bin<T, S>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S + Clone,
+{
+    let items = items
+        .into_iter()
+        .map(|item| SizedWrapper::new_generic(key_func.clone(), item))
+        .collect();
+
+    first_fit_decreasing_with_options(bin_size, items, options)
         .into_iter()
         .map(|bin| bin.map(|item| item.take()))
         .collect()
 }
 
+/// Pack items in bins using the [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing)
+/// bin packing algorithm, with the constraints in `options`.
+///
+/// Unlike [`first_fit_decreasing_with_options`], this doesn't panic or put items that are too large in their own bin,
+/// but returns a [`PackError`] instead.
+/// If any items are too large, all items are handed back in [`PackError::ItemTooLarge`],
+/// in the order they were given.
+pub fn try_first_fit_decreasing<T, S>(
+    bin_size: S,
+    items: Vec<T>,
+    options: PackOptions,
+) -> Result<Vec<Bin<T, S>>, PackError<Vec<T>>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    __internal_check_options(options)?;
+    let items = __internal_check_items(bin_size, items, T::size)?;
+
+    Ok(first_fit_decreasing_with_options(bin_size, items, options))
+}
+
+/// Pack items in bins using the [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing)
+/// bin packing algorithm, with the constraints in `options`.
+///
+/// Like [`try_first_fit_decreasing`], but the size of the items is determined by `key_func`,
+/// see [`first_fit_decreasing_by_key`].
+pub fn try_first_fit_decreasing_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: Vec<T>,
+    options: PackOptions,
+    key_func: SizeFunc,
+) -> Result<Vec<Bin<T, S>>, PackError<Vec<T>>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S + Clone,
+{
+    __internal_check_options(options)?;
+    let items = __internal_check_items(bin_size, items, &key_func)?;
+
+    Ok(first_fit_decreasing_with_options_by_key(
+        bin_size, items, options, key_func,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::{generate_test_bins, generate_test_set_a};
+    use crate::tests::{generate_test_bins, generate_test_set_a, MyItem};
 
     #[test]
     fn it_works() {
@@ -113,4 +189,79 @@ mod tests {
 
         assert_eq!(expected, result)
     }
+
+    #[test]
+    fn works_with_u64_sizes() {
+        const GIB: u64 = 1 << 30;
+
+        let result = first_fit_decreasing_by_key(
+            8 * GIB,
+            vec![5 * GIB, 3 * GIB, 4 * GIB, 4 * GIB],
+            |&size| size,
+        );
+
+        assert_eq!(2, result.len());
+        assert_eq!(&[5 * GIB, 3 * GIB], result[0].contents());
+        assert_eq!(&[4 * GIB, 4 * GIB], result[1].contents());
+    }
+
+    #[test]
+    fn respects_max_items() {
+        let (test_data, bin_size) = generate_test_set_a();
+
+        let result = first_fit_decreasing_with_options(
+            bin_size,
+            test_data,
+            PackOptions::new().with_max_items(2),
+        );
+
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![19, 1],  // 20
+                vec![19, 1],  // 20
+                vec![10, 10], // 20
+                vec![10, 4],  // 14
+                vec![3, 1],   // 4
+                vec![1],      // 1
+            ],
+        );
+
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn max_items_applies_to_zero_sized_items() {
+        let result = first_fit_decreasing_with_options_by_key(
+            10,
+            vec![0usize; 5],
+            PackOptions::new().with_max_items(2),
+            |&size| size,
+        );
+
+        assert_eq!(
+            vec![2, 2, 1],
+            result.iter().map(Bin::len).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn try_with_options_reports_errors() {
+        assert_eq!(
+            Err(PackError::ZeroCapacity),
+            try_first_fit_decreasing(
+                10,
+                vec![MyItem { size: 1 }],
+                PackOptions::new().with_max_items(0)
+            )
+        );
+        assert_eq!(
+            Ok(generate_test_bins(10, vec![vec![5, 3], vec![2]])),
+            try_first_fit_decreasing(
+                10,
+                vec![MyItem { size: 2 }, MyItem { size: 5 }, MyItem { size: 3 }],
+                PackOptions::new().with_max_items(2)
+            )
+        );
+    }
 }
@@ -1 +1,5 @@
+pub mod best_fit_decreasing;
+pub mod exact;
 pub mod first_fit_decreasing;
+pub mod modified_first_fit_decreasing;
+pub mod variable_sized;
@@ -0,0 +1,391 @@
+use std::ops::Range;
+
+use crate::bounds::l1;
+use crate::error::{__internal_check_items, __internal_check_options};
+use crate::online::first_fit::__internal_first_fit;
+use crate::options::assert_valid_options;
+use crate::wrapper::SizedWrapper;
+use crate::{Bin, Pack, PackError, PackOptions, Size};
+
+/// Pack items in bins using the Modified-first-fit-decreasing (MFFD)
+/// bin packing algorithm by Johnson and Garey, with the constraints in `options`.
+///
+/// Items are divided into classes relative to `bin_size`:
+/// large items (more than 1/2), medium items (more than 1/3),
+/// small items (more than 11/71) and tiny items.
+/// Every large item gets a bin of its own, after which the bins are filled up
+/// with medium items first and then with pairs of small items.
+/// Whatever is left is packed [First-fit-decreasing](crate::offline::first_fit_decreasing).
+///
+/// This never uses more than `71/60` times the optimal number of bins (plus one),
+/// compared to `11/9` for First-fit-decreasing.
+/// That guarantee doesn't hold if the number of items per bin is limited.
+///
+/// Panics if `bin_size` is 0, or if the options leave no room for any items.
+pub fn modified_first_fit_decreasing<T, S>(
+    bin_size: S,
+    mut items: Vec<T>,
+    options: PackOptions,
+) -> Vec<Bin<T, S>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    assert!(bin_size > S::ZERO, "Bin size must be greater than 0");
+    assert_valid_options(options);
+
+    // Sort the items in decreasing order
+    items.sort_unstable_by(|a, b| b.size().total_cmp(&a.size()));
+
+    let lower_bound = l1(bin_size, &items).max(
+        options
+            .max_items
+            .map_or(0, |max_items| items.len().div_ceil(max_items)),
+    );
+
+    __internal_modified_first_fit_decreasing(bin_size, items, lower_bound, options.max_items)
+}
+
+/// Pack items in bins using the Modified-first-fit-decreasing (MFFD)
+/// bin packing algorithm by Johnson and Garey, with the constraints in `options`.
+///
+/// Unlike [`modified_first_fit_decreasing`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+///
+/// This function will be cloned for each item
+/// (but if it's a simple function pointer or a non-capturing closure, then it is a no-op).
+pub fn modified_first_fit_decreasing_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: Vec<T>,
+    options: PackOptions,
+    key_func: SizeFunc,
+) -> Vec<Bin<T, S>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S + Clone,
+{
+    let items = items
+        .into_iter()
+        .map(|item| SizedWrapper::new_generic(key_func.clone(), item))
+        .collect();
+
+    modified_first_fit_decreasing(bin_size, items, options)
+        .into_iter()
+        .map(|bin| bin.map(|item| item.take()))
+        .collect()
+}
+
+/// Pack items in bins using the Modified-first-fit-decreasing (MFFD)
+/// bin packing algorithm by Johnson and Garey, with the constraints in `options`.
+///
+/// Unlike [`modified_first_fit_decreasing`], this doesn't panic or put items that are too large in their own bin,
+/// but returns a [`PackError`] instead.
+/// If any items are too large, all items are handed back in [`PackError::ItemTooLarge`],
+/// in the order they were given.
+pub fn try_modified_first_fit_decreasing<T, S>(
+    bin_size: S,
+    items: Vec<T>,
+    options: PackOptions,
+) -> Result<Vec<Bin<T, S>>, PackError<Vec<T>>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    __internal_check_options(options)?;
+    let items = __internal_check_items(bin_size, items, T::size)?;
+
+    Ok(modified_first_fit_decreasing(bin_size, items, options))
+}
+
+/// Pack items in bins using the Modified-first-fit-decreasing (MFFD)
+/// bin packing algorithm by Johnson and Garey, with the constraints in `options`.
+///
+/// Like [`try_modified_first_fit_decreasing`], but the size of the items is determined by `key_func`,
+/// see [`modified_first_fit_decreasing_by_key`].
+pub fn try_modified_first_fit_decreasing_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: Vec<T>,
+    options: PackOptions,
+    key_func: SizeFunc,
+) -> Result<Vec<Bin<T, S>>, PackError<Vec<T>>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S + Clone,
+{
+    __internal_check_options(options)?;
+    let items = __internal_check_items(bin_size, items, &key_func)?;
+
+    Ok(modified_first_fit_decreasing_by_key(
+        bin_size, items, options, key_func,
+    ))
+}
+
+/// Pack the (decreasingly sorted) items, with at most `max_items` items in every bin if given.
+#[doc(hidden)]
+pub(crate) fn __internal_modified_first_fit_decreasing<T, S>(
+    bin_size: S,
+    items: Vec<T>,
+    lower_bound: usize,
+    max_items: Option<usize>,
+) -> Vec<Bin<T, S>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    // Check whether `count` more items can be put in the bin
+    let has_room = |bin: &Bin<T, S>, count: usize| {
+        max_items.is_none_or(|max_items| bin.len() + count <= max_items)
+    };
+
+    let sizes = items.iter().map(|item| item.size()).collect::<Vec<_>>();
+    let mut items = Remaining {
+        sizes: &sizes,
+        items: items.into_iter().map(Some).collect(),
+    };
+
+    // Since the items are sorted, every class is a range of them
+    let large_end = sizes
+        .iter()
+        .take_while(|&&size| size > bin_size || size > bin_size - size)
+        .count();
+    let medium_end = large_end
+        + sizes[large_end..]
+            .iter()
+            .take_while(|&&size| size > bin_size - size - size)
+            .count();
+    let small_end = medium_end
+        + sizes[medium_end..]
+            .iter()
+            .take_while(|&&size| size.to_f64() * 71.0 > bin_size.to_f64() * 11.0)
+            .count();
+
+    let medium = large_end..medium_end;
+    let small = medium_end..small_end;
+    let rest = large_end..sizes.len();
+
+    // Every large item gets a bin of its own
+    let mut bins = (0..large_end)
+        .map(|idx| Bin::with_item_and_size(bin_size, items.take(idx), sizes[idx]))
+        .collect::<Vec<_>>();
+    let mut has_medium = vec![false; bins.len()];
+
+    // Going forward, put the largest medium item that fits in every bin
+    for (bin, has_medium) in bins.iter_mut().zip(has_medium.iter_mut()) {
+        let Some(smallest) = items.smallest(medium.clone()) else {
+            break;
+        };
+        if sizes[smallest] > bin.remaining_capacity || !has_room(bin, 1) {
+            continue;
+        }
+
+        if let Some(idx) = items.largest_fitting(medium.clone(), bin.remaining_capacity) {
+            bin.add_with_size(items.take(idx), sizes[idx]);
+            *has_medium = true;
+        }
+    }
+
+    // Going backward, put the smallest small item and the largest small item that still fits
+    // in every bin without a medium item
+    for (bin, _) in bins
+        .iter_mut()
+        .zip(has_medium)
+        .rev()
+        .filter(|(_, has_medium)| !has_medium)
+    {
+        let Some(smallest) = items.smallest(small.clone()) else {
+            break;
+        };
+        let Some(second_smallest) = items.smallest(small.start..smallest) else {
+            break;
+        };
+        if sizes[smallest] + sizes[second_smallest] > bin.remaining_capacity || !has_room(bin, 2) {
+            continue;
+        }
+
+        bin.add_with_size(items.take(smallest), sizes[smallest]);
+        if let Some(idx) = items.largest_fitting(small.clone(), bin.remaining_capacity) {
+            bin.add_with_size(items.take(idx), sizes[idx]);
+        }
+    }
+
+    // Going forward, keep putting the largest item that fits in every bin
+    for bin in bins.iter_mut() {
+        while has_room(bin, 1) {
+            let Some(idx) = items.largest_fitting(rest.clone(), bin.remaining_capacity) else {
+                break;
+            };
+            bin.add_with_size(items.take(idx), sizes[idx]);
+        }
+    }
+
+    // Pack whatever is left in new bins
+    let remaining = items.items.into_iter().flatten().collect::<Vec<_>>();
+    if !remaining.is_empty() {
+        let lower_bound = lower_bound.saturating_sub(bins.len());
+        bins.extend(__internal_first_fit(
+            bin_size,
+            remaining,
+            lower_bound,
+            max_items,
+        ));
+    }
+
+    bins
+}
+
+/// The items that haven't been put in a bin yet.
+struct Remaining<'a, T, S> {
+    sizes: &'a [S],
+    items: Vec<Option<T>>,
+}
+
+impl<T, S> Remaining<'_, T, S>
+where
+    S: Size,
+{
+    fn take(&mut self, idx: usize) -> T {
+        self.items[idx].take().expect("Item was already packed")
+    }
+
+    /// Find the smallest remaining item in the range.
+    fn smallest(&self, range: Range<usize>) -> Option<usize> {
+        range.rev().find(|&idx| self.items[idx].is_some())
+    }
+
+    /// Find the largest remaining item in the range that is at most `capacity`.
+    fn largest_fitting(&self, range: Range<usize>, capacity: S) -> Option<usize> {
+        range
+            .into_iter()
+            .find(|&idx| self.items[idx].is_some() && self.sizes[idx] <= capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offline::first_fit_decreasing::first_fit_decreasing;
+    use crate::tests::{generate_test_bins, generate_test_set_a, MyItem};
+
+    fn items(sizes: &[usize]) -> Vec<MyItem> {
+        sizes.iter().map(|&size| MyItem { size }).collect()
+    }
+
+    #[test]
+    fn it_works() {
+        let (test_data, bin_size) = generate_test_set_a();
+
+        let result = modified_first_fit_decreasing(bin_size, test_data, PackOptions::default());
+
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![19, 1],          // 20
+                vec![19, 1],          // 20
+                vec![10, 10],         // 20
+                vec![10, 4, 3, 1, 1], //19
+            ],
+        );
+
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn improves_on_first_fit_decreasing() {
+        let test_data = [5, 11, 4, 4, 12, 4];
+
+        // First fit decreasing needs 3 bins: [12, 5], [11, 4, 4] and [4]
+        assert_eq!(3, first_fit_decreasing(20, items(&test_data)).len());
+
+        let result = modified_first_fit_decreasing(20, items(&test_data), PackOptions::default());
+
+        // The small items are put in pairs next to the large ones
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![12, 4, 4], // 20
+                vec![11, 4, 5], // 20
+            ],
+        );
+
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let test_data = items(&[5, 11, 4, 4, 12, 4])
+            .into_iter()
+            .map(|item| item.make_unpacked())
+            .collect::<Vec<_>>();
+
+        let result =
+            modified_first_fit_decreasing_by_key(20, test_data, PackOptions::default(), |item| {
+                item.size
+            });
+
+        assert_eq!(2, result.len());
+    }
+
+    #[test]
+    fn try_modified_first_fit_decreasing_reports_errors() {
+        assert_eq!(
+            Err(PackError::ZeroCapacity),
+            try_modified_first_fit_decreasing(0, items(&[5]), PackOptions::default())
+        );
+        assert_eq!(
+            Err(PackError::ItemTooLarge(items(&[5, 25]))),
+            try_modified_first_fit_decreasing(20, items(&[5, 25]), PackOptions::default())
+        );
+        assert_eq!(
+            Err(PackError::Overflow),
+            try_modified_first_fit_decreasing_by_key(
+                200u8,
+                vec![150u8, 150],
+                PackOptions::default(),
+                |&size| size
+            )
+        );
+        assert_eq!(
+            Ok(generate_test_bins(20, vec![vec![12, 4, 4], vec![11, 4, 5]])),
+            try_modified_first_fit_decreasing(
+                20,
+                items(&[5, 11, 4, 4, 12, 4]),
+                PackOptions::default()
+            )
+        );
+    }
+
+    #[test]
+    fn respects_max_items() {
+        let result = modified_first_fit_decreasing(
+            20,
+            items(&[5, 11, 4, 4, 12, 4]),
+            PackOptions::new().with_max_items(2),
+        );
+
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![12, 5], // 17
+                vec![11, 4], // 15
+                vec![4, 4],  // 8
+            ],
+        );
+
+        assert_eq!(expected, result);
+        assert_eq!(
+            Err(PackError::ZeroCapacity),
+            try_modified_first_fit_decreasing(
+                20,
+                items(&[5]),
+                PackOptions::new().with_max_items(0)
+            )
+        );
+    }
+
+    #[test]
+    fn empty_input_returns_no_bins() {
+        let result = modified_first_fit_decreasing::<MyItem, _>(20, vec![], PackOptions::default());
+
+        assert_eq!(result, vec![]);
+    }
+}
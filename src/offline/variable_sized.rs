@@ -0,0 +1,461 @@
+use crate::error::{__internal_check_items, __internal_check_options};
+use crate::options::assert_valid_options;
+use crate::wrapper::SizedWrapper;
+use crate::{Bin, Pack, PackError, PackOptions, Size};
+
+/// A type of bin that can be used by [`variable_sized`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BinType<S = usize> {
+    /// How much fits in a bin of this type.
+    pub capacity: S,
+    /// What it costs to use a single bin of this type.
+    pub cost: usize,
+    /// How many bins of this type are available, or `None` if there is no limit.
+    pub limit: Option<usize>,
+}
+
+impl<S> BinType<S> {
+    /// Create a new bin type, of which an unlimited number of bins is available.
+    pub const fn new(capacity: S, cost: usize) -> Self {
+        Self {
+            capacity,
+            cost,
+            limit: None,
+        }
+    }
+
+    /// Limit the number of bins of this type that can be used.
+    pub const fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+/// Pack items in bins of different sizes, trying to minimize the total cost of the bins,
+/// with the constraints in `options`.
+///
+/// The items are first packed [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing),
+/// opening bins of the type that has the lowest cost per unit of capacity whenever an item doesn't fit
+/// in any of the open bins. Afterwards, every bin is swapped for the cheapest type that still holds its contents.
+///
+/// Every returned bin records the index of its type in `bin_types`, see [`Bin::bin_type`].
+///
+/// If none of the available bin types is big enough for an item,
+/// it is put in its own bin of the biggest type that is still available.
+///
+/// Panics if `bin_types` is empty, if any of the bin types has a capacity of 0,
+/// if the options leave no room for any items,
+/// or if there aren't enough bins available to hold all items.
+pub fn variable_sized<T, S>(
+    bin_types: &[BinType<S>],
+    items: Vec<T>,
+    options: PackOptions,
+) -> Vec<Bin<T, S>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    assert!(!bin_types.is_empty(), "There must be at least one bin type");
+    assert!(
+        bin_types.iter().all(|bin_type| bin_type.capacity > S::ZERO),
+        "Bin size must be greater than 0"
+    );
+    assert_valid_options(options);
+
+    match pack(bin_types, items, options.max_items) {
+        Ok(bins) => bins,
+        Err(_) => panic!("Not enough bins available"),
+    }
+}
+
+/// Pack items in bins of different sizes, trying to minimize the total cost of the bins,
+/// with the constraints in `options`.
+///
+/// Unlike [`variable_sized`], this doesn't panic or put items that are too large in their own bin,
+/// but returns a [`PackError`] instead.
+/// If any items are too large for every bin type, all items are handed back in [`PackError::ItemTooLarge`],
+/// and if there aren't enough bins available, all items are handed back in [`PackError::NotEnoughBins`].
+pub fn try_variable_sized<T, S>(
+    bin_types: &[BinType<S>],
+    items: Vec<T>,
+    options: PackOptions,
+) -> Result<Vec<Bin<T, S>>, PackError<Vec<T>>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    __internal_check_options(options)?;
+
+    if !bin_types.iter().all(|bin_type| bin_type.capacity > S::ZERO) {
+        return Err(PackError::ZeroCapacity);
+    }
+
+    let biggest_capacity = bin_types
+        .iter()
+        .map(|bin_type| bin_type.capacity)
+        .max_by(|a, b| a.total_cmp(b))
+        .ok_or(PackError::ZeroCapacity)?;
+    let items = __internal_check_items(biggest_capacity, items, T::size)?;
+
+    pack(bin_types, items, options.max_items).map_err(PackError::NotEnoughBins)
+}
+
+/// Pack the items, or hand them back if there aren't enough bins available.
+fn pack<T, S>(
+    bin_types: &[BinType<S>],
+    mut items: Vec<T>,
+    max_items: Option<usize>,
+) -> Result<Vec<Bin<T, S>>, Vec<T>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    // Sort the items in decreasing order
+    items.sort_unstable_by(|a, b| b.size().total_cmp(&a.size()));
+
+    let sizes = items.iter().map(|item| item.size()).collect::<Vec<_>>();
+    let Some((bin_type_indices, assignment)) =
+        __internal_variable_sized(bin_types, &sizes, max_items)
+    else {
+        return Err(items);
+    };
+
+    let mut bins = bin_type_indices
+        .into_iter()
+        .map(|bin_type_idx| {
+            let mut bin = Bin::with_capacity(bin_types[bin_type_idx].capacity);
+            bin.bin_type = Some(bin_type_idx);
+            bin
+        })
+        .collect::<Vec<_>>();
+
+    for ((item, size), bin_idx) in items.into_iter().zip(sizes).zip(assignment) {
+        bins[bin_idx].add_with_size(item, size);
+    }
+
+    Ok(bins)
+}
+
+/// Pack items in bins of different sizes, trying to minimize the total cost of the bins,
+/// with the constraints in `options`.
+///
+/// Unlike [`variable_sized`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+///
+/// This function will be cloned for each item
+/// (but if it's a simple function pointer or a non-capturing closure, then it is a no-op).
+pub fn variable_sized_by_key<T, S, SizeFunc>(
+    bin_types: &[BinType<S>],
+    items: Vec<T>,
+    options: PackOptions,
+    key_func: SizeFunc,
+) -> Vec<Bin<T, S>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S + Clone,
+{
+    let items = items
+        .into_iter()
+        .map(|item| SizedWrapper::new_generic(key_func.clone(), item))
+        .collect();
+
+    variable_sized(bin_types, items, options)
+        .into_iter()
+        .map(|bin| bin.map(|item| item.take()))
+        .collect()
+}
+
+/// Pack items in bins of different sizes, trying to minimize the total cost of the bins,
+/// with the constraints in `options`.
+///
+/// Like [`try_variable_sized`], but the size of the items is determined by `key_func`,
+/// see [`variable_sized_by_key`].
+pub fn try_variable_sized_by_key<T, S, SizeFunc>(
+    bin_types: &[BinType<S>],
+    items: Vec<T>,
+    options: PackOptions,
+    key_func: SizeFunc,
+) -> Result<Vec<Bin<T, S>>, PackError<Vec<T>>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S + Clone,
+{
+    let items = items
+        .into_iter()
+        .map(|item| SizedWrapper::new_generic(key_func.clone(), item))
+        .collect();
+
+    match try_variable_sized(bin_types, items, options) {
+        Ok(bins) => Ok(bins
+            .into_iter()
+            .map(|bin| bin.map(|item| item.take()))
+            .collect()),
+        Err(err) => Err(err.map(|items| items.into_iter().map(|item| item.take()).collect())),
+    }
+}
+
+/// Compute the total cost of bins packed by [`variable_sized`].
+///
+/// Bins that don't have a type (because they were packed by a different algorithm)
+/// don't count towards the total.
+pub fn total_cost<T, S>(bin_types: &[BinType<S>], bins: &[Bin<T, S>]) -> usize {
+    bins.iter()
+        .filter_map(|bin| bin.bin_type())
+        .map(|bin_type_idx| bin_types[bin_type_idx].cost)
+        .sum()
+}
+
+/// Assign the (decreasingly sorted) sizes to bins, with at most `max_items` sizes in every bin if given.
+///
+/// Returns the type of every bin that is used, and the bin index for every size,
+/// or `None` if there aren't enough bins available.
+#[doc(hidden)]
+pub(crate) fn __internal_variable_sized<S>(
+    bin_types: &[BinType<S>],
+    sizes: &[S],
+    max_items: Option<usize>,
+) -> Option<(Vec<usize>, Vec<usize>)>
+where
+    S: Size,
+{
+    let mut available = bin_types
+        .iter()
+        .map(|bin_type| bin_type.limit.unwrap_or(usize::MAX))
+        .collect::<Vec<_>>();
+
+    // The type and remaining capacity of every open bin, and the total size and number of its contents
+    let mut bin_type_indices = Vec::<usize>::new();
+    let mut remaining_capacities = Vec::<S>::new();
+    let mut used = Vec::<S>::new();
+    let mut counts = Vec::<usize>::new();
+    let mut assignment = Vec::with_capacity(sizes.len());
+
+    for &size in sizes {
+        // Find the first bin that the item fits in
+        let bin_idx = match remaining_capacities.iter().zip(&counts).position(
+            |(&remaining_capacity, &count)| {
+                size <= remaining_capacity && max_items.is_none_or(|max_items| count < max_items)
+            },
+        ) {
+            Some(bin_idx) => bin_idx,
+            None => {
+                let bin_type_idx = cheapest_per_unit(bin_types, &available, size)
+                    .or_else(|| biggest(bin_types, &available))?;
+                available[bin_type_idx] -= 1;
+
+                bin_type_indices.push(bin_type_idx);
+                remaining_capacities.push(bin_types[bin_type_idx].capacity);
+                used.push(S::ZERO);
+                counts.push(0);
+                remaining_capacities.len() - 1
+            }
+        };
+
+        remaining_capacities[bin_idx] = remaining_capacities[bin_idx].saturating_sub(size);
+        used[bin_idx] = used[bin_idx] + size;
+        counts[bin_idx] += 1;
+        assignment.push(bin_idx);
+    }
+
+    // Swap every bin for the cheapest type that still holds its contents
+    for (bin_type_idx, &used) in bin_type_indices.iter_mut().zip(&used) {
+        available[*bin_type_idx] += 1;
+
+        if let Some(cheapest_idx) = bin_types
+            .iter()
+            .enumerate()
+            .filter(|(idx, bin_type)| available[*idx] > 0 && used <= bin_type.capacity)
+            .min_by_key(|(_, bin_type)| bin_type.cost)
+            .map(|(idx, _)| idx)
+            .filter(|&idx| bin_types[idx].cost < bin_types[*bin_type_idx].cost)
+        {
+            *bin_type_idx = cheapest_idx;
+        }
+
+        available[*bin_type_idx] -= 1;
+    }
+
+    Some((bin_type_indices, assignment))
+}
+
+/// Find the available bin type that fits `size`, with the lowest cost per unit of capacity.
+/// Ties are broken in favour of the biggest bin.
+fn cheapest_per_unit<S>(bin_types: &[BinType<S>], available: &[usize], size: S) -> Option<usize>
+where
+    S: Size,
+{
+    bin_types
+        .iter()
+        .enumerate()
+        .filter(|(idx, bin_type)| available[*idx] > 0 && size <= bin_type.capacity)
+        .min_by(|(_, a), (_, b)| {
+            // Compare a.cost / a.capacity with b.cost / b.capacity without dividing
+            (a.cost as f64 * b.capacity.to_f64())
+                .total_cmp(&(b.cost as f64 * a.capacity.to_f64()))
+                .then(b.capacity.total_cmp(&a.capacity))
+        })
+        .map(|(idx, _)| idx)
+}
+
+/// Find the biggest bin type that is still available.
+fn biggest<S>(bin_types: &[BinType<S>], available: &[usize]) -> Option<usize>
+where
+    S: Size,
+{
+    bin_types
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| available[*idx] > 0)
+        .max_by(|(_, a), (_, b)| a.capacity.total_cmp(&b.capacity))
+        .map(|(idx, _)| idx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{generate_test_set_a, MyItem};
+
+    const SMALL: BinType = BinType::new(10, 3);
+    const LARGE: BinType = BinType::new(20, 5);
+
+    fn items(sizes: &[usize]) -> Vec<MyItem> {
+        sizes.iter().map(|&size| MyItem { size }).collect()
+    }
+
+    fn summary(bins: &[Bin<MyItem>]) -> Vec<(Option<usize>, Vec<usize>)> {
+        bins.iter()
+            .map(|bin| {
+                (
+                    bin.bin_type(),
+                    bin.contents().iter().map(|item| item.size).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_works() {
+        let (test_data, _) = generate_test_set_a();
+        let bin_types = [SMALL, LARGE];
+
+        let result = variable_sized(&bin_types, test_data, PackOptions::default());
+
+        assert_eq!(
+            vec![
+                (Some(1), vec![19, 1]),
+                (Some(1), vec![19, 1]),
+                (Some(1), vec![10, 10]),
+                (Some(1), vec![10, 4, 3, 1, 1]),
+            ],
+            summary(&result)
+        );
+        assert_eq!(20, total_cost(&bin_types, &result));
+    }
+
+    #[test]
+    fn downsizes_bins() {
+        let bin_types = [SMALL, LARGE];
+
+        let result = variable_sized(&bin_types, items(&[8, 15]), PackOptions::default());
+
+        // The 8 is first packed in a large bin, which is then swapped for a cheaper small one
+        assert_eq!(
+            vec![(Some(1), vec![15]), (Some(0), vec![8])],
+            summary(&result)
+        );
+        assert_eq!(8, total_cost(&bin_types, &result));
+    }
+
+    #[test]
+    fn respects_limits() {
+        let bin_types = [SMALL, LARGE.with_limit(1)];
+
+        let result = variable_sized(&bin_types, items(&[15, 8, 7]), PackOptions::default());
+
+        assert_eq!(
+            vec![(Some(1), vec![15]), (Some(0), vec![8]), (Some(0), vec![7])],
+            summary(&result)
+        );
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let test_data = items(&[8, 15])
+            .into_iter()
+            .map(|item| item.make_unpacked())
+            .collect::<Vec<_>>();
+
+        let result =
+            variable_sized_by_key(&[SMALL, LARGE], test_data, PackOptions::default(), |item| {
+                item.size
+            });
+
+        assert_eq!(
+            vec![Some(1), Some(0)],
+            result.iter().map(|bin| bin.bin_type()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough bins available")]
+    fn panics_when_running_out_of_bins() {
+        variable_sized(
+            &[SMALL.with_limit(1)],
+            items(&[8, 7]),
+            PackOptions::default(),
+        );
+    }
+
+    #[test]
+    fn try_variable_sized_hands_back_items() {
+        assert_eq!(
+            Err(PackError::NotEnoughBins(items(&[8, 7]))),
+            try_variable_sized(
+                &[SMALL.with_limit(1)],
+                items(&[8, 7]),
+                PackOptions::default()
+            )
+        );
+        assert_eq!(
+            Err(PackError::ItemTooLarge(items(&[25, 5]))),
+            try_variable_sized(&[SMALL, LARGE], items(&[25, 5]), PackOptions::default())
+        );
+        assert_eq!(
+            Err(PackError::ZeroCapacity),
+            try_variable_sized::<MyItem, usize>(&[], items(&[5]), PackOptions::default())
+        );
+    }
+
+    #[test]
+    fn respects_max_items() {
+        let bin_types = [SMALL, LARGE];
+
+        let result = variable_sized(
+            &bin_types,
+            items(&[9, 1, 2, 1]),
+            PackOptions::new().with_max_items(2),
+        );
+
+        // The second bin only holds 2, so it fits in a small bin
+        assert_eq!(
+            vec![(Some(1), vec![9, 2]), (Some(0), vec![1, 1])],
+            summary(&result)
+        );
+        assert_eq!(
+            Err(PackError::ZeroCapacity),
+            try_variable_sized(
+                &bin_types,
+                items(&[5]),
+                PackOptions::new().with_max_items(0)
+            )
+        );
+        assert_eq!(
+            Err(PackError::NotEnoughBins(items(&[1, 1, 1]))),
+            try_variable_sized(
+                &[LARGE.with_limit(1)],
+                items(&[1, 1, 1]),
+                PackOptions::new().with_max_items(2)
+            )
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{Bin, Pack, Size};
+
+use super::online_packer::OnlinePackerError;
+use super::OnlinePacker;
+
+/// This implements the [Best-fit](https://en.wikipedia.org/wiki/Best-fit_bin_packing)
+/// bin packing algorithm.
+///
+/// When a new item arrives, it is put into the open bin with the least remaining capacity
+/// that can still hold it.
+/// If none of the open bins are big enough, a new bin is opened to hold the new item.
+///
+/// Unlike [`NextKFitPacker`](super::next_k_fit::NextKFitPacker), bins are never closed
+/// to make room for new ones: a bin is only closed (and returned from [`OnlinePacker::try_add`])
+/// once it is completely full, since no other item could ever be added to it.
+#[derive(Debug)]
+pub struct BestFitPacker<Item, SizeFn, S = usize> {
+    bins: Vec<Bin<Item, S>>,
+    max_bin_size: S,
+    size_fn: SizeFn,
+}
+
+impl<Item, SizeFn, S> BestFitPacker<Item, SizeFn, S>
+where
+    S: Size,
+{
+    /// Create a new BestFitPacker.
+    ///
+    /// Each bin will fit a maximum of `size`.
+    ///
+    /// The size of a single element is determined by the `size_fn`.
+    ///
+    /// Panics if `size` is 0.
+    pub fn new_with_key(size: S, size_fn: SizeFn) -> Self {
+        assert!(size > S::ZERO, "size must be greater than 0");
+
+        Self {
+            bins: Vec::new(),
+            max_bin_size: size,
+            size_fn,
+        }
+    }
+}
+
+impl<Item, S> BestFitPacker<Item, fn(&Item) -> S, S>
+where
+    S: Size,
+{
+    /// Create a new BestFitPacker.
+    ///
+    /// This function requires that `Item` implements [`Pack`].
+    /// If your type doesn't, consider using [`new_with_key`](BestFitPacker::new_with_key).
+    pub fn new(size: S) -> BestFitPacker<Item, fn(&Item) -> S, S>
+    where
+        Item: Pack<S>,
+    {
+        fn pack_size<S>(item: &impl Pack<S>) -> S {
+            item.size()
+        }
+
+        BestFitPacker::<Item, _, S>::new_with_key(size, pack_size)
+    }
+}
+
+impl<Item, SizeFn, S> OnlinePacker<Item, S> for BestFitPacker<Item, SizeFn, S>
+where
+    SizeFn: Fn(&Item) -> S,
+    S: Size,
+{
+    fn try_add(&mut self, item: Item) -> Result<Vec<Bin<Item, S>>, OnlinePackerError<Item>> {
+        let item_size = (self.size_fn)(&item);
+        if item_size > self.max_bin_size {
+            return Err(OnlinePackerError::ItemTooLarge(item));
+        }
+
+        // Find the open bin with the least remaining capacity that still fits the item.
+        // Ties are broken in favour of the bin that was opened first.
+        let best_bin_idx = self
+            .bins
+            .iter()
+            .enumerate()
+            .filter(|(_, bin)| item_size <= bin.remaining_capacity)
+            .min_by(|(_, a), (_, b)| a.remaining_capacity.total_cmp(&b.remaining_capacity))
+            .map(|(bin_idx, _)| bin_idx);
+
+        let bin_idx = match best_bin_idx {
+            Some(bin_idx) => {
+                self.bins[bin_idx].add_with_size(item, item_size);
+                bin_idx
+            }
+            None => {
+                self.bins
+                    .push(Bin::with_item_and_size(self.max_bin_size, item, item_size));
+                self.bins.len() - 1
+            }
+        };
+
+        // A full bin can't take any more items, so there is no reason to keep it open.
+        if self.bins[bin_idx].remaining_capacity == S::ZERO {
+            return Ok(vec![self.bins.remove(bin_idx)]);
+        }
+
+        Ok(Vec::new())
+    }
+
+    fn finalize(self) -> Vec<Bin<Item, S>> {
+        self.bins
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{generate_test_bins, generate_test_set_a, MyItem};
+
+    use super::*;
+
+    #[test]
+    fn empty_input_returns_no_bins() {
+        let packer: BestFitPacker<MyItem, _> = BestFitPacker::new(10);
+        assert_eq!(packer.finalize(), vec![]);
+
+        let packer: BestFitPacker<MyItem, _> = BestFitPacker::new(10);
+        assert_eq!(packer.pack_all(vec![].into_iter()).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_dataset_a() {
+        let (test_data, bin_size) = generate_test_set_a();
+        let packer = BestFitPacker::new(bin_size);
+
+        let bins = packer.pack_all(test_data.into_iter()).unwrap();
+
+        // Full bins are emitted as soon as they are closed,
+        // the remaining ones are emitted in the order they were opened.
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![10, 10],           // 20
+                vec![1, 1, 1, 1, 3, 4], // 11
+                vec![10],               // 10
+                vec![19],               // 19
+                vec![19],               // 19
+            ],
+        );
+
+        assert_eq!(expected, bins);
+    }
+
+    #[test]
+    fn picks_the_tightest_bin() {
+        let mut packer = BestFitPacker::new(10);
+
+        assert!(packer.add(MyItem { size: 5 }).is_empty());
+        assert!(packer.add(MyItem { size: 7 }).is_empty());
+
+        // Both bins fit an item of size 3, but the second one is tighter.
+        let closed = packer.add(MyItem { size: 3 });
+        assert_eq!(closed, generate_test_bins(10, vec![vec![7, 3]]));
+
+        assert_eq!(packer.finalize(), generate_test_bins(10, vec![vec![5]]));
+    }
+
+    #[test]
+    fn rejects_items_that_are_too_large() {
+        let mut packer = BestFitPacker::new(10);
+
+        assert!(matches!(
+            packer.try_add(MyItem { size: 11 }),
+            Err(OnlinePackerError::ItemTooLarge(MyItem { size: 11 }))
+        ));
+    }
+}
@@ -0,0 +1,490 @@
+use std::collections::HashMap;
+
+use crate::tournament_tree::TournamentTree;
+use crate::{Bin, Pack, Size};
+
+use super::online_packer::OnlinePackerError;
+
+/// Identifies an item that was inserted into a [`DynamicPacker`],
+/// so that it can be removed again later.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct ItemHandle(usize);
+
+#[derive(Debug)]
+struct Entry<Item, S> {
+    handle: ItemHandle,
+    size: S,
+    item: Item,
+}
+
+/// A packer for [dynamic bin packing](https://en.wikipedia.org/wiki/Bin_packing_problem#Dynamic_bin_packing),
+/// where items can leave again after they have been packed.
+///
+/// New items are packed [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing),
+/// which fills up the space that removed items left behind.
+///
+/// Removing items can leave many bins partially empty. Bins that become completely empty are dropped.
+/// If a repack threshold is set (see [`with_repack_threshold`](DynamicPacker::with_repack_threshold)),
+/// all items are packed again from scratch once the [`fragmentation`](DynamicPacker::fragmentation)
+/// goes over that threshold. Note that this may move items to a different bin.
+///
+/// A repack takes `O(n log n)` time for `n` items. With a low threshold, or items that can't be packed
+/// much tighter, that could happen on every removal;
+/// [`with_min_removals_between_repacks`](DynamicPacker::with_min_removals_between_repacks)
+/// spreads out the cost over a number of removals.
+#[derive(Debug)]
+pub struct DynamicPacker<Item, SizeFn, S = usize> {
+    /// The bins in the order they were opened, `None` once they are empty until they are cleared out.
+    bins: Vec<Option<Bin<Entry<Item, S>, S>>>,
+    tree: TournamentTree<S>,
+    locations: HashMap<ItemHandle, usize>,
+    /// The number of bins that hold at least one item.
+    bin_count: usize,
+    /// The number of bins that were dropped, but still take up a slot in `bins` and a leaf in `tree`.
+    dropped: usize,
+    /// The total size of all items.
+    total_size: S,
+    next_handle: usize,
+    max_bin_size: S,
+    size_fn: SizeFn,
+    repack_threshold: Option<f64>,
+    min_removals_between_repacks: usize,
+    removals_since_repack: usize,
+}
+
+impl<Item, SizeFn, S> DynamicPacker<Item, SizeFn, S>
+where
+    S: Size,
+{
+    /// Create a new DynamicPacker.
+    ///
+    /// Each bin will fit a maximum of `size`.
+    ///
+    /// The size of a single element is determined by the `size_fn`.
+    ///
+    /// Panics if `size` is 0.
+    pub fn new_with_key(size: S, size_fn: SizeFn) -> Self {
+        assert!(size > S::ZERO, "size must be greater than 0");
+
+        Self {
+            bins: Vec::new(),
+            tree: TournamentTree::with_capacity(1),
+            locations: HashMap::new(),
+            bin_count: 0,
+            dropped: 0,
+            total_size: S::ZERO,
+            next_handle: 0,
+            max_bin_size: size,
+            size_fn,
+            repack_threshold: None,
+            min_removals_between_repacks: 1,
+            removals_since_repack: 0,
+        }
+    }
+
+    /// Repack all items once the [`fragmentation`](DynamicPacker::fragmentation)
+    /// goes over `threshold` after removing an item.
+    ///
+    /// Panics if `threshold` is not between 0 and 1.
+    pub fn with_repack_threshold(mut self, threshold: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&threshold),
+            "threshold must be between 0 and 1"
+        );
+
+        self.repack_threshold = Some(threshold);
+        self
+    }
+
+    /// Only repack automatically once at least `count` items were removed since the last repack,
+    /// even if the [`fragmentation`](DynamicPacker::fragmentation) goes over the threshold before that.
+    ///
+    /// By default every removal can lead to a repack.
+    ///
+    /// Panics if `count` is 0.
+    pub fn with_min_removals_between_repacks(mut self, count: usize) -> Self {
+        assert!(
+            count > 0,
+            "Minimum number of removals must be greater than 0"
+        );
+
+        self.min_removals_between_repacks = count;
+        self
+    }
+
+    /// Remove an item from the packer, freeing up the space it took.
+    ///
+    /// Returns `None` if the item was already removed.
+    pub fn try_remove(&mut self, handle: ItemHandle) -> Option<Item> {
+        let bin_idx = self.locations.remove(&handle)?;
+
+        let bin = self.bins[bin_idx]
+            .as_mut()
+            .expect("Items are only in bins that weren't dropped");
+        let index = bin
+            .contents
+            .iter()
+            .position(|entry| entry.handle == handle)
+            .expect("Item is in the bin it was assigned to");
+        let size = bin.contents[index].size;
+        let entry = bin.remove_with_size(index, size);
+        self.total_size = self.total_size.saturating_sub(size);
+
+        if bin.contents.is_empty() {
+            self.drop_bin(bin_idx);
+        } else {
+            self.tree.update(bin_idx, bin.remaining_capacity);
+        }
+
+        self.removals_since_repack += 1;
+        if let Some(threshold) = self.repack_threshold {
+            if self.removals_since_repack >= self.min_removals_between_repacks
+                && self.fragmentation() > threshold
+            {
+                self.repack();
+            }
+        }
+
+        Some(entry.item)
+    }
+
+    /// Remove an item from the packer, freeing up the space it took.
+    ///
+    /// Like [`DynamicPacker::try_remove`], but will panic if the item was already removed.
+    pub fn remove(&mut self, handle: ItemHandle) -> Item {
+        match self.try_remove(handle) {
+            Some(item) => item,
+            None => panic!("Item was already removed from packer"),
+        }
+    }
+
+    /// Drop the bin at `bin_idx`, which just became empty.
+    ///
+    /// Once at least half of the slots belong to dropped bins, they are cleared out
+    /// and the tree is rebuilt with the remaining bins, which keeps them in the order they were opened.
+    fn drop_bin(&mut self, bin_idx: usize) {
+        self.bins[bin_idx] = None;
+        self.tree.close(bin_idx);
+        self.bin_count -= 1;
+        self.dropped += 1;
+
+        if 2 * self.dropped >= self.bins.len() {
+            self.bins.retain(Option::is_some);
+            self.tree = TournamentTree::with_capacity(self.bins.len());
+            for (bin_idx, bin) in self.bins.iter().flatten().enumerate() {
+                self.tree.push(bin.remaining_capacity);
+                for entry in &bin.contents {
+                    self.locations.insert(entry.handle, bin_idx);
+                }
+            }
+            self.dropped = 0;
+        }
+    }
+
+    /// Get an item that is currently in the packer.
+    pub fn get(&self, handle: ItemHandle) -> Option<&Item> {
+        let bin_idx = *self.locations.get(&handle)?;
+
+        self.bins[bin_idx]
+            .as_ref()?
+            .contents
+            .iter()
+            .find(|entry| entry.handle == handle)
+            .map(|entry| &entry.item)
+    }
+
+    /// Get the index of the bin an item is currently in.
+    ///
+    /// Bins keep their index until the items are repacked,
+    /// or until the bins that were dropped are cleared out, once they make up half of all bins.
+    pub fn bin_of(&self, handle: ItemHandle) -> Option<usize> {
+        self.locations.get(&handle).copied()
+    }
+
+    /// Get the remaining capacity of the bin at `bin_idx`, or `None` if there is no such bin
+    /// or it was dropped.
+    pub fn free_capacity(&self, bin_idx: usize) -> Option<S> {
+        self.bins
+            .get(bin_idx)?
+            .as_ref()
+            .map(|bin| bin.remaining_capacity)
+    }
+
+    /// Get the number of items in the packer.
+    pub fn len(&self) -> usize {
+        self.locations.len()
+    }
+
+    /// Check whether there are no items in the packer.
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty()
+    }
+
+    /// Get the number of bins that hold at least one item.
+    pub fn bin_count(&self) -> usize {
+        self.bin_count
+    }
+
+    /// Get the fraction of the capacity of the bins in use that is free,
+    /// from 0 (all bins are full) to 1.
+    ///
+    /// This takes constant time, since the total size of the items is kept up to date.
+    pub fn fragmentation(&self) -> f64 {
+        if self.bin_count == 0 {
+            return 0.0;
+        }
+
+        let capacity = self.bin_count as f64 * self.max_bin_size.to_f64();
+        (capacity - self.total_size.to_f64()) / capacity
+    }
+
+    /// Pack all items again from scratch, using
+    /// [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing).
+    ///
+    /// This will usually free up bins, but may move items to a different bin.
+    /// It takes `O(n log n)` time for `n` items.
+    pub fn repack(&mut self) {
+        self.removals_since_repack = 0;
+
+        let mut entries = self
+            .bins
+            .drain(..)
+            .flatten()
+            .flat_map(|bin| bin.contents)
+            .collect::<Vec<_>>();
+
+        // Sort the items in decreasing order, keeping older items first
+        entries.sort_by(|a, b| b.size.total_cmp(&a.size).then(a.handle.0.cmp(&b.handle.0)));
+
+        self.tree = TournamentTree::with_capacity(self.bins.capacity());
+        self.bin_count = 0;
+        self.dropped = 0;
+        for entry in entries {
+            self.place(entry);
+        }
+    }
+
+    /// Put an entry in the first bin it fits in, opening a new one if it doesn't fit anywhere.
+    fn place(&mut self, entry: Entry<Item, S>) -> ItemHandle {
+        let handle = entry.handle;
+        let size = entry.size;
+
+        let bin_idx = match self.tree.first_fit(size) {
+            Some(bin_idx) => {
+                let bin = self.bins[bin_idx]
+                    .as_mut()
+                    .expect("The tree only holds bins that weren't dropped");
+                bin.add_with_size(entry, size);
+                self.tree.update(bin_idx, bin.remaining_capacity);
+                bin_idx
+            }
+            None => {
+                let bin = Bin::with_item_and_size(self.max_bin_size, entry, size);
+                self.tree.push(bin.remaining_capacity);
+                self.bins.push(Some(bin));
+                self.bin_count += 1;
+                self.bins.len() - 1
+            }
+        };
+
+        self.locations.insert(handle, bin_idx);
+        handle
+    }
+
+    /// Take all the items out of the packer, returning the bins they are in.
+    ///
+    /// Bins that were dropped because they became empty are left out.
+    pub fn into_bins(self) -> Vec<Bin<Item, S>> {
+        self.bins
+            .into_iter()
+            .flatten()
+            .map(|bin| bin.map(|entry| entry.item))
+            .collect()
+    }
+}
+
+impl<Item, SizeFn, S> DynamicPacker<Item, SizeFn, S>
+where
+    SizeFn: Fn(&Item) -> S,
+    S: Size,
+{
+    /// Try inserting a new item into the packer.
+    ///
+    /// Returns a handle that can be used to remove the item again.
+    pub fn try_insert(&mut self, item: Item) -> Result<ItemHandle, OnlinePackerError<Item>> {
+        let size = (self.size_fn)(&item);
+        if size > self.max_bin_size {
+            return Err(OnlinePackerError::ItemTooLarge(item));
+        }
+
+        let handle = ItemHandle(self.next_handle);
+        self.next_handle += 1;
+        self.total_size = self.total_size + size;
+
+        Ok(self.place(Entry { handle, size, item }))
+    }
+
+    /// Insert a new item into the packer.
+    ///
+    /// Like [`DynamicPacker::try_insert`], but will panic if the item cannot be added.
+    pub fn insert(&mut self, item: Item) -> ItemHandle {
+        match self.try_insert(item) {
+            Ok(handle) => handle,
+            Err(_) => panic!("Could not add item to packer"),
+        }
+    }
+}
+
+impl<Item, S> DynamicPacker<Item, fn(&Item) -> S, S>
+where
+    S: Size,
+{
+    /// Create a new DynamicPacker.
+    ///
+    /// This function requires that `Item` implements [`Pack`].
+    /// If your type doesn't, consider using [`new_with_key`](DynamicPacker::new_with_key).
+    pub fn new(size: S) -> DynamicPacker<Item, fn(&Item) -> S, S>
+    where
+        Item: Pack<S>,
+    {
+        fn pack_size<S>(item: &impl Pack<S>) -> S {
+            item.size()
+        }
+
+        DynamicPacker::<Item, _, S>::new_with_key(size, pack_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{generate_test_bins, generate_test_set_a, MyItem};
+
+    use super::*;
+
+    #[test]
+    fn test_dataset_a() {
+        let (test_data, bin_size) = generate_test_set_a();
+        let mut packer = DynamicPacker::new(bin_size);
+
+        for item in test_data {
+            packer.insert(item);
+        }
+
+        // Without removals this is plain first fit
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![1, 1, 1, 1, 3, 4], // 11
+                vec![10, 10],           // 20
+                vec![10],               // 10
+                vec![19],               // 19
+                vec![19],               // 19
+            ],
+        );
+
+        assert_eq!(expected, packer.into_bins());
+    }
+
+    #[test]
+    fn removed_items_free_up_space() {
+        let mut packer = DynamicPacker::new(10);
+
+        let a = packer.insert(MyItem { size: 6 });
+        let b = packer.insert(MyItem { size: 6 });
+        let d = packer.insert(MyItem { size: 3 });
+        assert_eq!(Some(0), packer.bin_of(a));
+        assert_eq!(Some(1), packer.bin_of(b));
+        assert_eq!(Some(0), packer.bin_of(d));
+
+        assert_eq!(MyItem { size: 6 }, packer.remove(a));
+        assert_eq!(None, packer.try_remove(a));
+        assert_eq!(Some(7), packer.free_capacity(0));
+        assert_eq!(2, packer.bin_count());
+
+        // The space in the first bin is used again
+        let c = packer.insert(MyItem { size: 6 });
+        assert_eq!(Some(0), packer.bin_of(c));
+        assert_eq!(Some(&MyItem { size: 6 }), packer.get(c));
+        assert_eq!(3, packer.len());
+    }
+
+    #[test]
+    fn drops_empty_bins() {
+        let mut packer = DynamicPacker::new(10);
+
+        let handles = [6, 6, 6, 6].map(|size| packer.insert(MyItem { size }));
+
+        packer.remove(handles[1]);
+        assert_eq!(3, packer.bin_count());
+        assert_eq!(None, packer.free_capacity(1));
+        assert_eq!(0.4, packer.fragmentation());
+
+        // Items aren't put in a bin that was dropped, but in a new one
+        let e = packer.insert(MyItem { size: 3 });
+        assert_eq!(Some(0), packer.bin_of(e));
+        let f = packer.insert(MyItem { size: 6 });
+        assert_eq!(Some(4), packer.bin_of(f));
+
+        packer.remove(handles[2]);
+        assert_eq!(Some(3), packer.bin_of(handles[3]));
+
+        // Once half of the bins are dropped, the rest move up
+        packer.remove(handles[3]);
+        assert_eq!(Some(1), packer.bin_of(f));
+        assert_eq!(Some(4), packer.free_capacity(1));
+        assert_eq!(2, packer.bin_count());
+
+        assert_eq!(
+            generate_test_bins(10, vec![vec![6, 3], vec![6]]),
+            packer.into_bins()
+        );
+    }
+
+    #[test]
+    fn repacks_when_fragmented() {
+        let mut packer = DynamicPacker::new(10).with_repack_threshold(0.4);
+
+        let handles = [5, 5, 5, 5].map(|size| packer.insert(MyItem { size }));
+        assert_eq!(2, packer.bin_count());
+
+        // A quarter of the capacity is free, which is not over the threshold yet
+        packer.remove(handles[0]);
+        assert_eq!(2, packer.bin_count());
+        assert_eq!(0.25, packer.fragmentation());
+
+        // Now half of the capacity is free, so the remaining items are packed together
+        packer.remove(handles[2]);
+        assert_eq!(1, packer.bin_count());
+        assert_eq!(0.0, packer.fragmentation());
+        assert_eq!(Some(0), packer.bin_of(handles[1]));
+        assert_eq!(Some(0), packer.bin_of(handles[3]));
+    }
+
+    #[test]
+    fn waits_for_min_removals_between_repacks() {
+        let mut packer = DynamicPacker::new(10)
+            .with_repack_threshold(0.1)
+            .with_min_removals_between_repacks(2);
+
+        let handles = [5, 5, 5, 5].map(|size| packer.insert(MyItem { size }));
+
+        // The fragmentation is over the threshold, but only one item was removed
+        packer.remove(handles[0]);
+        assert_eq!(2, packer.bin_count());
+        assert_eq!(0.25, packer.fragmentation());
+
+        packer.remove(handles[2]);
+        assert_eq!(1, packer.bin_count());
+        assert_eq!(0.0, packer.fragmentation());
+    }
+
+    #[test]
+    fn rejects_items_that_are_too_large() {
+        let mut packer = DynamicPacker::new(10);
+
+        assert!(matches!(
+            packer.try_insert(MyItem { size: 11 }),
+            Err(OnlinePackerError::ItemTooLarge(MyItem { size: 11 }))
+        ));
+    }
+}
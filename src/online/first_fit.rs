@@ -1,14 +1,23 @@
-use crate::{wrapper::SizedWrapper, Bin, Pack};
+use crate::error::{__internal_check_items, __internal_check_options};
+use crate::options::assert_valid_options;
+use crate::{
+    tournament_tree::TournamentTree, wrapper::SizedWrapper, Bin, Pack, PackError, PackOptions, Size,
+};
+
+use super::online_packer::OnlinePackerError;
+use super::OnlinePacker;
 
 /// Pack items in bins using the [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing)
 /// bin packing algorithm.
-pub fn first_fit<T>(bin_size: usize, items: impl IntoIterator<Item = T>) -> Vec<Bin<T>>
+///
+/// All bins are returned once every item is packed;
+/// see [`FirstFitPacker`] to get bins back while items are still coming in.
+pub fn first_fit<T, S>(bin_size: S, items: impl IntoIterator<Item = T>) -> Vec<Bin<T, S>>
 where
-    T: Pack,
+    T: Pack<S>,
+    S: Size,
 {
-    assert!(bin_size > 0, "Bin size must be greater than 0");
-
-    __internal_first_fit(bin_size, items, 1)
+    first_fit_with_options(bin_size, items, PackOptions::default())
 }
 
 /// Pack items in bins using the [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing)
@@ -19,62 +28,350 @@ where
 ///
 /// This function will be cloned for each item
 /// (but if it's a simple function pointer or a non-capturing closure, then it is a no-op).
-pub fn first_fit_by_key<T, SizeFunc>(
-    bin_size: usize,
+pub fn first_fit_by_key<T, S, SizeFunc>(
+    bin_size: S,
     items: impl IntoIterator<Item = T>,
     key_func: SizeFunc,
-) -> Vec<Bin<T>>
+) -> Vec<Bin<T, S>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S + Clone,
+{
+    first_fit_with_options_by_key(bin_size, items, PackOptions::default(), key_func)
+}
+
+/// Pack items in bins using the [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing)
+/// bin packing algorithm, with the constraints in `options`.
+///
+/// Panics if `bin_size` is 0, or if the options leave no room for any items.
+pub fn first_fit_with_options<T, S>(
+    bin_size: S,
+    items: impl IntoIterator<Item = T>,
+    options: PackOptions,
+) -> Vec<Bin<T, S>>
 where
-    SizeFunc: Fn(&T) -> usize + Clone,
+    T: Pack<S>,
+    S: Size,
 {
-    assert!(bin_size > 0, "Bin size must be greater than 0");
+    assert!(bin_size > S::ZERO, "Bin size must be greater than 0");
+    assert_valid_options(options);
+
+    __internal_first_fit(bin_size, items, 1, options.max_items)
+}
 
-    __internal_first_fit(
+/// Pack items in bins using the [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing)
+/// bin packing algorithm, with the constraints in `options`.
+///
+/// Like [`first_fit_with_options`], but the size of the items is determined by `key_func`,
+/// see [`first_fit_by_key`].
+pub fn first_fit_with_options_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: impl IntoIterator<Item = T>,
+    options: PackOptions,
+    key_func: SizeFunc,
+) -> Vec<Bin<T, S>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S + Clone,
+{
+    first_fit_with_options(
         bin_size,
         items
             .into_iter()
-            .map(|item| SizedWrapper::new(key_func.clone(), item)),
-        1,
+            .map(|item| SizedWrapper::new_generic(key_func.clone(), item)),
+        options,
     )
     .into_iter()
     .map(|bin| bin.map(|item| item.take()))
     .collect()
 }
 
+/// Pack items in bins using the [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing)
+/// bin packing algorithm, with the constraints in `options`.
+///
+/// Unlike [`first_fit_with_options`], this doesn't panic or put items that are too large in their own bin,
+/// but returns a [`PackError`] instead.
+/// If any items are too large, all items are handed back in [`PackError::ItemTooLarge`],
+/// in the order they were given.
+pub fn try_first_fit<T, S>(
+    bin_size: S,
+    items: impl IntoIterator<Item = T>,
+    options: PackOptions,
+) -> Result<Vec<Bin<T, S>>, PackError<Vec<T>>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    __internal_check_options(options)?;
+    let items = __internal_check_items(bin_size, items.into_iter().collect(), T::size)?;
+
+    Ok(first_fit_with_options(bin_size, items, options))
+}
+
+/// Pack items in bins using the [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing)
+/// bin packing algorithm, with the constraints in `options`.
+///
+/// Like [`try_first_fit`], but the size of the items is determined by `key_func`,
+/// see [`first_fit_by_key`].
+pub fn try_first_fit_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: impl IntoIterator<Item = T>,
+    options: PackOptions,
+    key_func: SizeFunc,
+) -> Result<Vec<Bin<T, S>>, PackError<Vec<T>>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S + Clone,
+{
+    __internal_check_options(options)?;
+    let items = __internal_check_items(bin_size, items.into_iter().collect(), &key_func)?;
+
+    Ok(first_fit_with_options_by_key(
+        bin_size, items, options, key_func,
+    ))
+}
+
 #[doc(hidden)]
-pub(crate) fn __internal_first_fit<T>(
-    bin_size: usize,
+pub(crate) fn __internal_first_fit<T, S>(
+    bin_size: S,
     items: impl IntoIterator<Item = T>,
     lower_bound: usize,
-) -> Vec<Bin<T>>
+    max_items: Option<usize>,
+) -> Vec<Bin<T, S>>
 where
-    T: Pack,
+    T: Pack<S>,
+    S: Size,
 {
-    // Initialize bins
-    let mut bins = Vec::<Bin<T>>::with_capacity(lower_bound);
+    // Initialize bins, and a tree to quickly find the first bin an item fits in
+    let mut bins = Vec::<Bin<T, S>>::with_capacity(lower_bound);
+    let mut tree = TournamentTree::with_capacity(lower_bound);
     bins.push(Bin::with_capacity(bin_size));
+    tree.push(bin_size);
 
     for item in items.into_iter() {
         // Find the first bin that the item fits in
-        match bins
-            .iter_mut()
-            .filter(|bin| item.size() <= bin.remaining_capacity)
-            .next()
+        let bin_idx = match tree.first_fit(item.size()) {
+            Some(bin_idx) => {
+                let bin = &mut bins[bin_idx];
+                bin.add(item);
+                tree.update(bin_idx, bin.remaining_capacity);
+                bin_idx
+            }
+            None => {
+                let bin = Bin::with_item(bin_size, item);
+                let bin_idx = tree.push(bin.remaining_capacity);
+                bins.push(bin);
+                bin_idx
+            }
+        };
+
+        // Bins that hold the maximum number of items are full, whatever their remaining capacity
+        if max_items.is_some_and(|max_items| bins[bin_idx].len() >= max_items) {
+            tree.close(bin_idx);
+        }
+    }
+
+    bins
+}
+
+/// When a [`FirstFitPacker`] closes a bin, returning it from [`OnlinePacker::try_add`].
+///
+/// Closed bins don't take any more items, so closing them early
+/// may lead to more bins than keeping them open.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ClosingPolicy {
+    /// Close bins once they are completely full, since no other item could ever be added to them.
+    #[default]
+    WhenFull,
+    /// Close bins once their [`fill_ratio`](Bin::fill_ratio) reaches the threshold, between 0 and 1.
+    AtFillRatio(f64),
+    /// Keep all bins open until [`OnlinePacker::finalize`].
+    Never,
+}
+
+/// This implements the [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing)
+/// bin packing algorithm.
+///
+/// When a new item arrives, it is put into the first open bin that can still hold it.
+/// If none of the open bins are big enough, a new bin is opened to hold the new item.
+///
+/// Bins are closed according to the [`ClosingPolicy`], see [`with_closing_policy`](FirstFitPacker::with_closing_policy).
+/// By default, a bin is closed once it is completely full.
+/// With [`ClosingPolicy::Never`], this packs items in the same bins as [`first_fit`].
+#[derive(Debug)]
+pub struct FirstFitPacker<Item, SizeFn, S = usize> {
+    /// The bins in the order they were opened, `None` once they are closed until they are dropped.
+    bins: Vec<Option<Bin<Item, S>>>,
+    tree: TournamentTree<S>,
+    /// The number of bins that are closed, but still take up a slot in `bins` and a leaf in `tree`.
+    closed: usize,
+    max_bin_size: S,
+    max_items: Option<usize>,
+    closing_policy: ClosingPolicy,
+    size_fn: SizeFn,
+}
+
+impl<Item, SizeFn, S> FirstFitPacker<Item, SizeFn, S>
+where
+    S: Size,
+{
+    /// Create a new FirstFitPacker.
+    ///
+    /// Each bin will fit a maximum of `size`.
+    ///
+    /// The size of a single element is determined by the `size_fn`.
+    ///
+    /// Panics if `size` is 0.
+    pub fn new_with_key(size: S, size_fn: SizeFn) -> Self {
+        assert!(size > S::ZERO, "size must be greater than 0");
+
+        Self {
+            bins: Vec::new(),
+            tree: TournamentTree::with_capacity(1),
+            closed: 0,
+            max_bin_size: size,
+            max_items: None,
+            closing_policy: ClosingPolicy::default(),
+            size_fn,
+        }
+    }
+
+    /// Decide when bins are closed.
+    ///
+    /// Panics if the threshold of [`ClosingPolicy::AtFillRatio`] is not between 0 and 1.
+    pub fn with_closing_policy(mut self, closing_policy: ClosingPolicy) -> Self {
+        if let ClosingPolicy::AtFillRatio(threshold) = closing_policy {
+            assert!(
+                (0.0..=1.0).contains(&threshold),
+                "threshold must be between 0 and 1"
+            );
+        }
+
+        self.closing_policy = closing_policy;
+        self
+    }
+
+    /// Put at most `max_items` items in every bin.
+    ///
+    /// A bin that holds `max_items` items is closed right away, whatever the closing policy.
+    ///
+    /// Panics if `max_items` is 0.
+    pub fn with_max_items(mut self, max_items: usize) -> Self {
+        assert!(
+            max_items > 0,
+            "Maximum number of items must be greater than 0"
+        );
+
+        self.max_items = Some(max_items);
+        self
+    }
+
+    /// Check whether the bin should be closed according to the closing policy.
+    fn should_close(&self, bin: &Bin<Item, S>) -> bool {
+        if self
+            .max_items
+            .is_some_and(|max_items| bin.len() >= max_items)
         {
-            Some(bin) => bin.add(item),
-            None => bins.push(Bin::with_item(bin_size, item)),
+            return true;
         }
 
-        // TODO: Should be move bins that are full to a new vector to avoid having to iterate them?
+        match self.closing_policy {
+            ClosingPolicy::WhenFull => bin.remaining_capacity == S::ZERO,
+            ClosingPolicy::AtFillRatio(threshold) => bin.fill_ratio() >= threshold,
+            ClosingPolicy::Never => false,
+        }
     }
 
-    bins
+    /// Close the bin at `bin_idx`, and return it.
+    ///
+    /// Once at least half of the slots belong to closed bins, they are dropped
+    /// and the tree is rebuilt with the open bins, which keeps them in the order they were opened.
+    /// This takes amortized constant time per closed bin.
+    fn close(&mut self, bin_idx: usize) -> Option<Bin<Item, S>> {
+        let bin = self.bins[bin_idx].take();
+        self.tree.close(bin_idx);
+        self.closed += 1;
+
+        if 2 * self.closed >= self.bins.len() {
+            self.bins.retain(Option::is_some);
+            self.tree = TournamentTree::with_capacity(self.bins.len());
+            for bin in self.bins.iter().flatten() {
+                self.tree.push(bin.remaining_capacity);
+            }
+            self.closed = 0;
+        }
+
+        bin
+    }
+}
+
+impl<Item, S> FirstFitPacker<Item, fn(&Item) -> S, S>
+where
+    S: Size,
+{
+    /// Create a new FirstFitPacker.
+    ///
+    /// This function requires that `Item` implements [`Pack`].
+    /// If your type doesn't, consider using [`new_with_key`](FirstFitPacker::new_with_key).
+    pub fn new(size: S) -> FirstFitPacker<Item, fn(&Item) -> S, S>
+    where
+        Item: Pack<S>,
+    {
+        fn pack_size<S>(item: &impl Pack<S>) -> S {
+            item.size()
+        }
+
+        FirstFitPacker::<Item, _, S>::new_with_key(size, pack_size)
+    }
+}
+
+impl<Item, SizeFn, S> OnlinePacker<Item, S> for FirstFitPacker<Item, SizeFn, S>
+where
+    SizeFn: Fn(&Item) -> S,
+    S: Size,
+{
+    fn try_add(&mut self, item: Item) -> Result<Vec<Bin<Item, S>>, OnlinePackerError<Item>> {
+        let item_size = (self.size_fn)(&item);
+        if item_size > self.max_bin_size {
+            return Err(OnlinePackerError::ItemTooLarge(item));
+        }
+
+        let bin_idx = match self.tree.first_fit(item_size) {
+            Some(bin_idx) => {
+                let bin = self.bins[bin_idx]
+                    .as_mut()
+                    .expect("The tree only holds open bins");
+                bin.add_with_size(item, item_size);
+                self.tree.update(bin_idx, bin.remaining_capacity);
+                bin_idx
+            }
+            None => {
+                let bin = Bin::with_item_and_size(self.max_bin_size, item, item_size);
+                let bin_idx = self.tree.push(bin.remaining_capacity);
+                self.bins.push(Some(bin));
+                bin_idx
+            }
+        };
+
+        let bin = self.bins[bin_idx]
+            .as_ref()
+            .expect("The item was just added to this bin");
+        if !self.should_close(bin) {
+            return Ok(Vec::new());
+        }
+
+        Ok(self.close(bin_idx).into_iter().collect())
+    }
+
+    fn finalize(self) -> Vec<Bin<Item, S>> {
+        self.bins.into_iter().flatten().collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::{generate_test_bins, generate_test_set_a};
+    use crate::tests::{generate_test_bins, generate_test_set_a, random_numbers, MyItem};
 
     #[test]
     fn it_works() {
@@ -127,4 +424,258 @@ mod tests {
 
         assert_eq!(expected, result)
     }
+
+    #[test]
+    fn matches_linear_scan() {
+        let sizes = random_numbers(42, 50).take(2000).collect::<Vec<_>>();
+
+        // The straightforward first fit: scan all bins for every item
+        let mut expected = vec![Bin::with_capacity(50)];
+        for &size in &sizes {
+            match expected
+                .iter_mut()
+                .find(|bin| size <= bin.remaining_capacity)
+            {
+                Some(bin) => bin.add(MyItem { size }),
+                None => expected.push(Bin::with_item(50, MyItem { size })),
+            }
+        }
+
+        let result = first_fit(50, sizes.into_iter().map(|size| MyItem { size }));
+
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn works_with_float_sizes() {
+        let result = first_fit_by_key(1.0, vec![0.5, 0.7, 0.5, 0.3], |&size| size);
+
+        assert_eq!(2, result.len());
+        assert_eq!(&[0.5, 0.5], result[0].contents());
+        assert_eq!(&[0.7, 0.3], result[1].contents());
+    }
+
+    #[test]
+    fn try_first_fit_reports_errors() {
+        assert_eq!(
+            Err(PackError::ZeroCapacity),
+            try_first_fit(0, vec![MyItem { size: 1 }], PackOptions::default())
+        );
+
+        // All items are handed back, not just the ones that are too large
+        assert_eq!(
+            Err(PackError::ItemTooLarge(vec![
+                MyItem { size: 11 },
+                MyItem { size: 5 },
+                MyItem { size: 12 }
+            ])),
+            try_first_fit(
+                10,
+                vec![MyItem { size: 11 }, MyItem { size: 5 }, MyItem { size: 12 }],
+                PackOptions::default()
+            )
+        );
+
+        assert_eq!(
+            Err(PackError::Overflow),
+            try_first_fit_by_key(200u8, vec![150u8, 150], PackOptions::default(), |&size| {
+                size
+            })
+        );
+
+        assert_eq!(
+            Ok(generate_test_bins(10, vec![vec![5, 5]])),
+            try_first_fit(
+                10,
+                vec![MyItem { size: 5 }, MyItem { size: 5 }],
+                PackOptions::default()
+            )
+        );
+    }
+
+    #[test]
+    fn respects_max_items() {
+        let (test_data, bin_size) = generate_test_set_a();
+
+        let result =
+            first_fit_with_options(bin_size, test_data, PackOptions::new().with_max_items(3));
+
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![1, 1, 1], // 3
+                vec![1, 3, 4], // 8
+                vec![10, 10],  // 20
+                vec![10],      // 10
+                vec![19],      // 19
+                vec![19],      // 19
+            ],
+        );
+
+        assert_eq!(expected, result);
+        assert_eq!(
+            Err(PackError::ZeroCapacity),
+            try_first_fit(
+                10,
+                vec![MyItem { size: 1 }],
+                PackOptions::new().with_max_items(0)
+            )
+        );
+    }
+
+    #[test]
+    fn packer_closes_bins_with_max_items() {
+        let (test_data, bin_size) = generate_test_set_a();
+        let mut packer = FirstFitPacker::new(bin_size).with_max_items(3);
+
+        let mut closed = Vec::new();
+        for item in test_data {
+            closed.extend(packer.add(item));
+        }
+
+        // Bins are closed as soon as they hold 3 items, or are full
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![1, 1, 1], // 3
+                vec![1, 3, 4], // 8
+                vec![10, 10],  // 20
+            ],
+        );
+
+        assert_eq!(expected, closed);
+        assert_eq!(
+            generate_test_bins(20, vec![vec![10], vec![19], vec![19]]),
+            packer.finalize()
+        );
+    }
+
+    #[test]
+    fn packer_empty_input_returns_no_bins() {
+        let packer: FirstFitPacker<MyItem, _> = FirstFitPacker::new(10);
+        assert_eq!(packer.finalize(), vec![]);
+
+        let packer: FirstFitPacker<MyItem, _> = FirstFitPacker::new(10);
+        assert_eq!(packer.pack_all(vec![].into_iter()).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn packer_closes_full_bins() {
+        let mut packer = FirstFitPacker::new(10);
+
+        assert!(packer.add(MyItem { size: 7 }).is_empty());
+        assert!(packer.add(MyItem { size: 5 }).is_empty());
+
+        // Both bins fit an item of size 3, but the first one comes first
+        let closed = packer.add(MyItem { size: 3 });
+        assert_eq!(closed, generate_test_bins(10, vec![vec![7, 3]]));
+
+        assert!(packer.add(MyItem { size: 4 }).is_empty());
+        assert_eq!(packer.finalize(), generate_test_bins(10, vec![vec![5, 4]]));
+    }
+
+    #[test]
+    fn packer_closes_bins_at_fill_ratio() {
+        let (test_data, bin_size) = generate_test_set_a();
+        let packer =
+            FirstFitPacker::new(bin_size).with_closing_policy(ClosingPolicy::AtFillRatio(0.5));
+
+        let bins = packer.pack_all(test_data.into_iter()).unwrap();
+
+        // Bins are emitted as soon as they are at least half full,
+        // so every 10 closes its own bin instead of being paired up with another one
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![1, 1, 1, 1, 3, 4], // 11
+                vec![10],               // 10
+                vec![10],               // 10
+                vec![10],               // 10
+                vec![19],               // 19
+                vec![19],               // 19
+            ],
+        );
+
+        assert_eq!(expected, bins);
+    }
+
+    #[test]
+    fn packer_reclaims_closed_bins() {
+        let sizes = random_numbers(7, 50).take(2000).collect::<Vec<_>>();
+        let mut packer =
+            FirstFitPacker::new(50).with_closing_policy(ClosingPolicy::AtFillRatio(0.7));
+
+        let mut bins = Vec::new();
+        for &size in &sizes {
+            bins.extend(packer.add(MyItem { size }));
+
+            // The closed bins never take up more slots than the open ones, plus the one just closed
+            let open = packer.bins.iter().flatten().count();
+            assert!(packer.bins.len() <= 2 * open + 1);
+        }
+        bins.extend(packer.finalize());
+
+        // Compare with first fit over a list that the closed bins are removed from
+        let mut open = Vec::<Bin<MyItem>>::new();
+        let mut expected = Vec::new();
+        for size in sizes {
+            let bin_idx = match open.iter().position(|bin| bin.remaining_capacity >= size) {
+                Some(bin_idx) => {
+                    open[bin_idx].add(MyItem { size });
+                    bin_idx
+                }
+                None => {
+                    open.push(Bin::with_item(50, MyItem { size }));
+                    open.len() - 1
+                }
+            };
+            if open[bin_idx].fill_ratio() >= 0.7 {
+                expected.push(open.remove(bin_idx));
+            }
+        }
+        expected.extend(open);
+
+        assert_eq!(expected, bins);
+    }
+
+    #[test]
+    fn packer_never_closing_matches_first_fit() {
+        let (test_data, bin_size) = generate_test_set_a();
+        let mut packer = FirstFitPacker::new(bin_size).with_closing_policy(ClosingPolicy::Never);
+
+        for item in test_data {
+            assert!(packer.add(item).is_empty());
+        }
+
+        let (test_data, _) = generate_test_set_a();
+        assert_eq!(first_fit(bin_size, test_data), packer.finalize());
+    }
+
+    #[test]
+    fn packer_rejects_items_that_are_too_large() {
+        let packer = FirstFitPacker::new(10);
+
+        let result = packer.pack_all(
+            vec![MyItem { size: 10 }, MyItem { size: 11 }, MyItem { size: 2 }].into_iter(),
+        );
+
+        let Err((err, packer, mut rest, closed)) = result else {
+            panic!("The item of size 11 should be rejected");
+        };
+        assert_eq!(OnlinePackerError::ItemTooLarge(MyItem { size: 11 }), err);
+        assert_eq!(
+            PackError::ItemTooLarge(MyItem { size: 11 }),
+            PackError::from(err)
+        );
+        assert_eq!(generate_test_bins(10, vec![vec![10]]), closed);
+        assert_eq!(Some(MyItem { size: 2 }), rest.next());
+        assert_eq!(packer.finalize(), vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "threshold must be between 0 and 1")]
+    fn packer_rejects_invalid_threshold() {
+        let _: FirstFitPacker<MyItem, _> =
+            FirstFitPacker::new(10).with_closing_policy(ClosingPolicy::AtFillRatio(1.5));
+    }
 }
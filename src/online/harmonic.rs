@@ -0,0 +1,229 @@
+use crate::{Bin, Pack, Size};
+
+use super::online_packer::OnlinePackerError;
+use super::OnlinePacker;
+
+/// This implements the [Harmonic-k](https://en.wikipedia.org/wiki/Harmonic_bin_packing)
+/// bin packing algorithm.
+///
+/// Items are divided into `k` classes by their size:
+/// class `j` (for `j < k`) holds the items with a size in `(size / (j + 1), size / j]`,
+/// and class `k` holds all items of at most `size / k`.
+/// A single bin is kept open for every class.
+///
+/// A bin of class `j < k` fits exactly `j` items of its class,
+/// so it is closed as soon as it holds that many.
+/// The items of class `k` are packed [Next-fit](https://en.wikipedia.org/wiki/Next-fit_bin_packing):
+/// its bin is closed once an item of that class doesn't fit in it anymore.
+#[derive(Debug)]
+pub struct HarmonicPacker<Item, SizeFn, S = usize> {
+    /// The open bin of class `j` is at index `j - 1`.
+    bins: Vec<Bin<Item, S>>,
+    max_bin_size: S,
+    size_fn: SizeFn,
+}
+
+impl<Item, SizeFn, S> HarmonicPacker<Item, SizeFn, S>
+where
+    S: Size,
+{
+    /// Create a new HarmonicPacker.
+    ///
+    /// It will divide items into `k` classes,
+    /// and each bin will fit a maximum of `size`.
+    ///
+    /// The size of a single element is determined by the `size_fn`.
+    ///
+    /// Panics if `k` or `size` is 0.
+    pub fn new_with_key(k: usize, size: S, size_fn: SizeFn) -> Self {
+        assert_ne!(k, 0, "k must be greater than 0");
+        assert!(size > S::ZERO, "size must be greater than 0");
+
+        Self {
+            bins: (0..k).map(|_| Bin::with_capacity(size)).collect::<Vec<_>>(),
+            max_bin_size: size,
+            size_fn,
+        }
+    }
+
+    /// Get the class of an item with the given size.
+    ///
+    /// An item in class `j < k` satisfies `j * size <= max_bin_size < (j + 1) * size`.
+    fn class_of(&self, item_size: S) -> usize {
+        let k = self.bins.len();
+        if item_size == S::ZERO {
+            return k;
+        }
+
+        let class = self.max_bin_size.div_floor(item_size).min(k);
+
+        // With float sizes the division can round up, so that `class` items don't actually fit
+        if class > 1 && class < k && !self.fits_times(item_size, class) {
+            return class - 1;
+        }
+
+        class
+    }
+
+    /// Check whether `count` items of the given size fit in a bin together.
+    fn fits_times(&self, item_size: S, count: usize) -> bool {
+        (1..count)
+            .try_fold(item_size, |total, _| total.checked_add(item_size))
+            .is_some_and(|total| total <= self.max_bin_size)
+    }
+}
+
+impl<Item, S> HarmonicPacker<Item, fn(&Item) -> S, S>
+where
+    S: Size,
+{
+    /// Create a new HarmonicPacker.
+    ///
+    /// This function requires that `Item` implements [`Pack`].
+    /// If your type doesn't, consider using [`new_with_key`](HarmonicPacker::new_with_key).
+    pub fn new(k: usize, size: S) -> HarmonicPacker<Item, fn(&Item) -> S, S>
+    where
+        Item: Pack<S>,
+    {
+        fn pack_size<S>(item: &impl Pack<S>) -> S {
+            item.size()
+        }
+
+        HarmonicPacker::<Item, _, S>::new_with_key(k, size, pack_size)
+    }
+}
+
+impl<Item, SizeFn, S> OnlinePacker<Item, S> for HarmonicPacker<Item, SizeFn, S>
+where
+    SizeFn: Fn(&Item) -> S,
+    S: Size,
+{
+    fn try_add(&mut self, item: Item) -> Result<Vec<Bin<Item, S>>, OnlinePackerError<Item>> {
+        let item_size = (self.size_fn)(&item);
+        if item_size > self.max_bin_size {
+            return Err(OnlinePackerError::ItemTooLarge(item));
+        }
+
+        let class = self.class_of(item_size);
+        let k = self.bins.len();
+        let bin = &mut self.bins[class - 1];
+
+        if class == k {
+            // The smallest items are packed next fit
+            if item_size <= bin.remaining_capacity {
+                bin.add_with_size(item, item_size);
+                return Ok(Vec::new());
+            }
+
+            let mut new_bin = Bin::with_item_and_size(self.max_bin_size, item, item_size);
+            std::mem::swap(bin, &mut new_bin);
+
+            return Ok(vec![new_bin]);
+        }
+
+        bin.add_with_size(item, item_size);
+
+        // A bin of this class can't fit more than `class` items
+        if bin.contents.len() == class {
+            let mut closed_bin = Bin::with_capacity(self.max_bin_size);
+            std::mem::swap(bin, &mut closed_bin);
+
+            return Ok(vec![closed_bin]);
+        }
+
+        Ok(Vec::new())
+    }
+
+    fn finalize(mut self) -> Vec<Bin<Item, S>> {
+        self.bins.retain(|bin| !bin.contents.is_empty());
+        self.bins
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{generate_test_bins, generate_test_set_a, MyItem};
+
+    use super::*;
+
+    #[test]
+    fn empty_input_returns_no_bins() {
+        let packer: HarmonicPacker<MyItem, _> = HarmonicPacker::new(3, 10);
+        assert_eq!(packer.finalize(), vec![]);
+
+        let packer: HarmonicPacker<MyItem, _> = HarmonicPacker::new(3, 10);
+        assert_eq!(packer.pack_all(vec![].into_iter()).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_dataset_a_k3() {
+        let (test_data, bin_size) = generate_test_set_a();
+        let packer = HarmonicPacker::new(3, bin_size);
+
+        let bins = packer.pack_all(test_data.into_iter()).unwrap();
+
+        // The 19s are in class 1, the 10s in class 2 and everything else is small.
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![10, 10],           // 20
+                vec![19],               // 19
+                vec![19],               // 19
+                vec![10],               // 10
+                vec![1, 1, 1, 1, 3, 4], // 11
+            ],
+        );
+
+        assert_eq!(expected, bins);
+    }
+
+    #[test]
+    fn closes_bins_when_their_class_is_full() {
+        let mut packer = HarmonicPacker::new(4, 12);
+
+        // 4 is in class 3, so its bin is closed after the third one
+        assert!(packer.add(MyItem { size: 4 }).is_empty());
+        assert!(packer.add(MyItem { size: 4 }).is_empty());
+        assert_eq!(
+            packer.add(MyItem { size: 4 }),
+            generate_test_bins(12, vec![vec![4, 4, 4]])
+        );
+
+        // 3 and 2 are in the small class, which is closed when the next item doesn't fit
+        for _ in 0..4 {
+            assert!(packer.add(MyItem { size: 3 }).is_empty());
+        }
+        assert_eq!(
+            packer.add(MyItem { size: 2 }),
+            generate_test_bins(12, vec![vec![3, 3, 3, 3]])
+        );
+
+        assert_eq!(packer.finalize(), generate_test_bins(12, vec![vec![2]]));
+    }
+
+    #[test]
+    fn float_sizes_never_overflow() {
+        let mut packer = HarmonicPacker::new_with_key(8, 0.3, |&size: &f64| size);
+        // 0.3 / size rounds to 7, but 7 of these add up to just over 0.3
+        let size = 0.04285714285714286;
+
+        let mut closed = Vec::new();
+        for _ in 0..7 {
+            closed.extend(packer.add(size));
+        }
+
+        assert_eq!(1, closed.len());
+        assert_eq!(6, closed[0].len());
+        assert!(!closed[0].is_overflowing());
+    }
+
+    #[test]
+    fn rejects_items_that_are_too_large() {
+        let mut packer = HarmonicPacker::new(3, 10);
+
+        assert!(matches!(
+            packer.try_add(MyItem { size: 11 }),
+            Err(OnlinePackerError::ItemTooLarge(MyItem { size: 11 }))
+        ));
+    }
+}
@@ -1,4 +1,7 @@
+pub mod best_fit;
+pub mod dynamic;
 pub mod first_fit;
+pub mod harmonic;
 pub mod next_k_fit;
 pub mod online_packer;
 pub use online_packer::OnlinePacker;
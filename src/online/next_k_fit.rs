@@ -1,6 +1,4 @@
-use std::usize;
-
-use crate::{Bin, Pack};
+use crate::{Bin, Pack, Size};
 
 use super::OnlinePacker;
 
@@ -11,14 +9,20 @@ use super::OnlinePacker;
 /// When a new item arrives, we attempt to put it into any one of the open bins.
 /// If none of the open bins are big enough, the most-filled bin is closed,
 /// and a new bin is opened to hold the new item.
+///
+/// The number of items in a bin can be limited with [`with_max_items`](NextKFitPacker::with_max_items).
 #[derive(Debug)]
-pub struct NextKFitPacker<Item, SizeFn> {
-    bins: Vec<Bin<Item>>,
-    max_bin_size: usize,
+pub struct NextKFitPacker<Item, SizeFn, S = usize> {
+    bins: Vec<Bin<Item, S>>,
+    max_bin_size: S,
+    max_items: Option<usize>,
     size_fn: SizeFn,
 }
 
-impl<Item, SizeFn> NextKFitPacker<Item, SizeFn> {
+impl<Item, SizeFn, S> NextKFitPacker<Item, SizeFn, S>
+where
+    S: Size,
+{
     /// Create a new NextKFitPacker.
     ///
     /// It will keep open `k` bins,
@@ -27,43 +31,82 @@ impl<Item, SizeFn> NextKFitPacker<Item, SizeFn> {
     /// The size of a single element is determined by the `size_fn`.
     ///
     /// Panics if `k` or `size` is 0.
-    pub fn new_with_key(k: usize, size: usize, size_fn: SizeFn) -> Self {
+    pub fn new_with_key(k: usize, size: S, size_fn: SizeFn) -> Self {
         assert_ne!(k, 0, "k must be greater than 0");
-        assert_ne!(size, 0, "size must be greater than 0");
+        assert!(size > S::ZERO, "size must be greater than 0");
 
         Self {
             bins: (0..k).map(|_| Bin::with_capacity(size)).collect::<Vec<_>>(),
             max_bin_size: size,
+            max_items: None,
             size_fn,
         }
     }
+
+    /// Put at most `max_items` items in every bin.
+    ///
+    /// A bin that holds `max_items` items is closed right away, whatever its remaining capacity.
+    ///
+    /// Panics if `max_items` is 0.
+    pub fn with_max_items(mut self, max_items: usize) -> Self {
+        assert!(
+            max_items > 0,
+            "Maximum number of items must be greater than 0"
+        );
+
+        self.max_items = Some(max_items);
+        self
+    }
 }
 
-impl<Item> NextKFitPacker<Item, fn(&Item) -> usize> {
+impl<Item, S> NextKFitPacker<Item, fn(&Item) -> S, S>
+where
+    S: Size,
+{
     /// Create a new NextKFitPacker.
     ///
     /// This function requires that `Item` implements [`Pack`].
     /// If your type doesn't, consider using [`new_with_key`](NextKFitPacker::new_with_key).
-    pub fn new(k: usize, size: usize) -> NextKFitPacker<Item, fn(&Item) -> usize>
+    pub fn new(k: usize, size: S) -> NextKFitPacker<Item, fn(&Item) -> S, S>
     where
-        Item: Pack,
+        Item: Pack<S>,
     {
-        fn pack_size(item: &impl Pack) -> usize {
+        fn pack_size<S>(item: &impl Pack<S>) -> S {
             item.size()
         }
 
-        NextKFitPacker::<Item, _>::new_with_key(k, size, pack_size)
+        NextKFitPacker::<Item, _, S>::new_with_key(k, size, pack_size)
+    }
+}
+
+impl<Item, SizeFn, S> NextKFitPacker<Item, SizeFn, S>
+where
+    S: Size,
+{
+    /// Close the bin at `bin_idx` if it holds the maximum number of items,
+    /// replacing it with an empty one.
+    fn close_if_full(&mut self, bin_idx: usize) -> Option<Bin<Item, S>> {
+        let max_items = self.max_items?;
+        if self.bins[bin_idx].len() < max_items {
+            return None;
+        }
+
+        Some(std::mem::replace(
+            &mut self.bins[bin_idx],
+            Bin::with_capacity(self.max_bin_size),
+        ))
     }
 }
 
-impl<Item, SizeFn> OnlinePacker<Item> for NextKFitPacker<Item, SizeFn>
+impl<Item, SizeFn, S> OnlinePacker<Item, S> for NextKFitPacker<Item, SizeFn, S>
 where
-    SizeFn: Fn(&Item) -> usize,
+    SizeFn: Fn(&Item) -> S,
+    S: Size,
 {
     fn try_add(
         &mut self,
         item: Item,
-    ) -> Result<Vec<Bin<Item>>, super::online_packer::OnlinePackerError<Item>> {
+    ) -> Result<Vec<Bin<Item, S>>, super::online_packer::OnlinePackerError<Item>> {
         let item_size = (self.size_fn)(&item);
         if item_size > self.max_bin_size {
             return Err(super::online_packer::OnlinePackerError::ItemTooLarge(item));
@@ -72,7 +115,7 @@ where
         // See if the item fits in any of the open bins.
         // At the same time, keep track of the most-filled bin.
         let mut most_filled_bin_idx = 0;
-        let mut most_filled_bin_capacity = usize::MAX;
+        let mut most_filled_bin_capacity = self.max_bin_size;
         for (bin_idx, bin) in self.bins.iter_mut().enumerate() {
             if bin.remaining_capacity < most_filled_bin_capacity {
                 most_filled_bin_idx = bin_idx;
@@ -81,7 +124,7 @@ where
 
             if item_size <= bin.remaining_capacity {
                 bin.add_with_size(item, item_size);
-                return Ok(Vec::new());
+                return Ok(self.close_if_full(bin_idx).into_iter().collect());
             }
         }
 
@@ -94,10 +137,12 @@ where
 
         std::mem::swap(&mut self.bins[most_filled_bin_idx], &mut bin);
 
-        Ok(vec![bin])
+        let mut closed = vec![bin];
+        closed.extend(self.close_if_full(most_filled_bin_idx));
+        Ok(closed)
     }
 
-    fn finalize(mut self) -> Vec<Bin<Item>> {
+    fn finalize(mut self) -> Vec<Bin<Item, S>> {
         // TODO: maybe the remaining bins could be packed more efficiently?
         // Right now, we just return all the bins we have that aren't empty.
         self.bins.retain(|bin| !bin.contents.is_empty());
@@ -107,7 +152,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{generate_test_bins, generate_test_set_a, MyItem};
+    use crate::tests::{expected_test_bin, generate_test_bins, generate_test_set_a, MyItem};
 
     use super::*;
 
@@ -168,12 +213,7 @@ mod tests {
             // This bin is only produced after finalizing
             Some(vec![19]),
         ]
-        .map(|opt| {
-            opt.map(|vec| Bin {
-                contents: vec.iter().map(|i| MyItem { size: *i }).collect::<Vec<_>>(),
-                remaining_capacity: bin_size - vec.iter().sum::<usize>(),
-            })
-        })
+        .map(|opt| opt.map(|vec| expected_test_bin(bin_size, vec)))
         .into_iter();
 
         for item in test_data {
@@ -197,7 +237,7 @@ mod tests {
         let (test_data, bin_size) = generate_test_set_a();
         let packer = NextKFitPacker::new(2, bin_size);
 
-        let mut bins = packer.pack_all(test_data.into_iter()).unwrap();
+        let bins = packer.pack_all(test_data.into_iter()).unwrap();
 
         // With two bin lookahead, NextKFit does not give an optimal solution;
         // however, it emits the same solution as Next1Fit, above,
@@ -217,4 +257,26 @@ mod tests {
 
         assert_eq!(expected, bins);
     }
+
+    #[test]
+    fn max_items_closes_bins() {
+        let (test_data, bin_size) = generate_test_set_a();
+        let packer = NextKFitPacker::new(2, bin_size).with_max_items(3);
+
+        let bins = packer.pack_all(test_data.into_iter()).unwrap();
+
+        let expected = generate_test_bins(
+            20,
+            vec![
+                vec![1, 1, 1], // 3
+                vec![1, 3, 4], // 8
+                vec![10, 10],  // 20
+                vec![19],      // 19
+                vec![19],      // 19
+                vec![10],      // 10
+            ],
+        );
+
+        assert_eq!(expected, bins);
+    }
 }
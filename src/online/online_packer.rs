@@ -1,20 +1,25 @@
-use crate::Bin;
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+
+use crate::{Bin, PackError};
 
 /// This trait is implemented by online packers.
 /// These algorithms consume items one by one,
 /// and at each step they decide whether to close any bins
 /// (giving them away for later use) or not.
-pub trait OnlinePacker<Item> {
+///
+/// `S` is the type of the size of the bins, see [`Bin`].
+pub trait OnlinePacker<Item, S = usize> {
     /// Try adding a new item to the packer.
     ///
     /// If this results in any bins getting closed, they will be returned in the `Ok(Vec)`;
     /// if no bins can be closed yet, the Vec will be empty.
-    fn try_add(&mut self, item: Item) -> Result<Vec<Bin<Item>>, OnlinePackerError<Item>>;
+    fn try_add(&mut self, item: Item) -> Result<Vec<Bin<Item, S>>, OnlinePackerError<Item>>;
 
     /// Add a new item to the packer.
     ///
     /// Like [`OnlinePacker::try_add`], but will panic if the item cannot be added.
-    fn add(&mut self, item: Item) -> Vec<Bin<Item>> {
+    fn add(&mut self, item: Item) -> Vec<Bin<Item, S>> {
         match self.try_add(item) {
             Ok(bins) => bins,
             Err(_) => panic!("Could not add item to packer"),
@@ -24,7 +29,7 @@ pub trait OnlinePacker<Item> {
     /// No new items will be coming in.
     /// If there were any bins still open,
     /// this will close and return them.
-    fn finalize(self) -> Vec<Bin<Item>>;
+    fn finalize(self) -> Vec<Bin<Item, S>>;
 
     /// Helper function to process an entire sequence of items
     /// and return the bins in one go.
@@ -39,10 +44,11 @@ pub trait OnlinePacker<Item> {
     /// If an [`OnlinePackerError`] occurs in the middle of the process,
     /// this will stop iterating over the items,
     /// and return all the state we have so far.
+    #[allow(clippy::type_complexity)]
     fn pack_all<Iterable>(
         self,
         mut items: Iterable,
-    ) -> Result<Vec<Bin<Item>>, (OnlinePackerError<Item>, Self, Iterable, Vec<Bin<Item>>)>
+    ) -> Result<Vec<Bin<Item, S>>, (OnlinePackerError<Item>, Self, Iterable, Vec<Bin<Item, S>>)>
     where
         Self: Sized,
         Iterable: Iterator<Item = Item>,
@@ -62,9 +68,29 @@ pub trait OnlinePacker<Item> {
 }
 
 /// Error returned when an item cannot be added to an online packer.
-#[derive(Debug)]
+///
+/// This converts into the crate-wide [`PackError`], holding the item that couldn't be added.
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum OnlinePackerError<T> {
     /// The item is too big to fit into any bin that this packer can make:
     /// for example, the bins are size 10 and you're trying to pack an item of size 50.
     ItemTooLarge(T),
 }
+
+impl<T> Display for OnlinePackerError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OnlinePackerError::ItemTooLarge(_) => write!(f, "item is too large to fit in any bin"),
+        }
+    }
+}
+
+impl<T> Error for OnlinePackerError<T> where T: Debug {}
+
+impl<T> From<OnlinePackerError<T>> for PackError<T> {
+    fn from(err: OnlinePackerError<T>) -> Self {
+        match err {
+            OnlinePackerError::ItemTooLarge(item) => PackError::ItemTooLarge(item),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+/// Constraints on the bins, on top of their size, that the packing algorithms can take.
+///
+/// The default options don't add any constraints.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct PackOptions {
+    /// The maximum number of items in every bin, or `None` if there is no limit.
+    ///
+    /// A bin is full when either its capacity or its number of items is exhausted.
+    pub max_items: Option<usize>,
+}
+
+impl PackOptions {
+    /// Options without any constraints.
+    pub const fn new() -> Self {
+        Self { max_items: None }
+    }
+
+    /// Put at most `max_items` items in every bin.
+    pub const fn with_max_items(mut self, max_items: usize) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+}
+
+/// Panics if bins can't hold any items.
+pub(crate) fn assert_valid_options(options: PackOptions) {
+    assert!(
+        options.max_items != Some(0),
+        "Maximum number of items must be greater than 0"
+    );
+}
@@ -0,0 +1,247 @@
+//! Handling of items that are larger than the bins.
+//!
+//! By default the packing algorithms put such an item in a bin of its own,
+//! which is then [overflowing](crate::Bin::is_overflowing).
+//! [`handle_oversized`] lets you choose what happens to them before packing the rest.
+
+use std::cmp::Ordering;
+use std::fmt::{self, Debug};
+
+use crate::{Bin, Pack, PackError, Size};
+
+/// What to do with an item that is larger than the bins.
+pub enum OversizePolicy<T, S = usize> {
+    /// Refuse to pack anything, and hand all items back
+    /// in [`PackError::ItemTooLarge`].
+    Reject,
+    /// Put the item in a bin of its own, which will be overflowing.
+    OwnBin,
+    /// Split the item using the given function.
+    ///
+    /// The function gets the item and a size,
+    /// and returns a part of exactly that size along with the rest of the item.
+    /// Every part that fits in a bin gets a bin of its own,
+    /// and the rest is packed like any other item.
+    /// Parts and rests that are still too large are split again.
+    ///
+    /// Both must be smaller than the item that was split, see [`handle_oversized`].
+    Split(Box<dyn Fn(T, S) -> (T, T)>),
+}
+
+impl<T, S> OversizePolicy<T, S> {
+    /// Split items using the given function, see [`OversizePolicy::Split`].
+    pub fn split(split_fn: impl Fn(T, S) -> (T, T) + 'static) -> Self {
+        OversizePolicy::Split(Box::new(split_fn))
+    }
+}
+
+impl<T, S> Debug for OversizePolicy<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OversizePolicy::Reject => write!(f, "Reject"),
+            OversizePolicy::OwnBin => write!(f, "OwnBin"),
+            OversizePolicy::Split(_) => write!(f, "Split(..)"),
+        }
+    }
+}
+
+/// Take the items that are larger than `bin_size` out of `items`, and deal with them
+/// according to the `policy`.
+///
+/// Returns the items that fit in a bin, which can then be passed to any of the packing algorithms,
+/// and the bins that were made for the items that are too large.
+/// If the items are rejected, they are all handed back, in the order they were given.
+///
+/// Panics if [`OversizePolicy::Split`] returns a part or rest that isn't smaller than the item it split,
+/// since the item would then be split forever.
+#[allow(clippy::type_complexity)]
+pub fn handle_oversized<T, S>(
+    bin_size: S,
+    items: Vec<T>,
+    policy: OversizePolicy<T, S>,
+) -> Result<(Vec<T>, Vec<Bin<T, S>>), PackError<Vec<T>>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    handle_oversized_by_key(bin_size, items, policy, T::size)
+}
+
+/// Take the items that are larger than `bin_size` out of `items`, and deal with them
+/// according to the `policy`.
+///
+/// Unlike [`handle_oversized`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+#[allow(clippy::type_complexity)]
+pub fn handle_oversized_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: Vec<T>,
+    policy: OversizePolicy<T, S>,
+    key_func: SizeFunc,
+) -> Result<(Vec<T>, Vec<Bin<T, S>>), PackError<Vec<T>>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    if bin_size.partial_cmp(&S::ZERO) != Some(Ordering::Greater) {
+        return Err(PackError::ZeroCapacity);
+    }
+
+    // Rejected items are all handed back, so they can't be taken apart yet
+    let split_fn = match policy {
+        OversizePolicy::Reject if items.iter().any(|item| key_func(item) > bin_size) => {
+            return Err(PackError::ItemTooLarge(items));
+        }
+        OversizePolicy::Reject => return Ok((items, Vec::new())),
+        OversizePolicy::OwnBin => None,
+        OversizePolicy::Split(split_fn) => Some(split_fn),
+    };
+
+    let (oversized, mut fitting): (Vec<_>, Vec<_>) = items
+        .into_iter()
+        .partition(|item| key_func(item) > bin_size);
+
+    let mut bins = Vec::new();
+    match split_fn {
+        None => {
+            bins.extend(oversized.into_iter().map(|item| {
+                let size = key_func(&item);
+                Bin::with_item_and_size(bin_size, item, size)
+            }));
+        }
+        Some(split_fn) => {
+            for item in oversized {
+                // Parts that are still too large are split again
+                let mut pending = vec![item];
+                while let Some(item) = pending.pop() {
+                    let item_size = key_func(&item);
+                    if item_size <= bin_size {
+                        fitting.push(item);
+                        continue;
+                    }
+
+                    let (part, rest) = split_fn(item, bin_size);
+                    let part_size = key_func(&part);
+                    let rest_size = key_func(&rest);
+                    assert!(
+                        part_size < item_size && rest_size < item_size,
+                        "Splitting an item must leave parts that are smaller than the item"
+                    );
+
+                    pending.push(rest);
+                    if part_size > bin_size {
+                        pending.push(part);
+                    } else {
+                        bins.push(Bin::with_item_and_size(bin_size, part, part_size));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok((fitting, bins))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offline::first_fit_decreasing::first_fit_decreasing;
+    use crate::tests::{expected_test_bin, generate_test_bins, MyItem};
+
+    fn items(sizes: &[usize]) -> Vec<MyItem> {
+        sizes.iter().map(|&size| MyItem { size }).collect()
+    }
+
+    fn split(item: MyItem, size: usize) -> (MyItem, MyItem) {
+        (
+            MyItem { size },
+            MyItem {
+                size: item.size - size,
+            },
+        )
+    }
+
+    #[test]
+    fn reject() {
+        assert_eq!(
+            Err(PackError::ItemTooLarge(items(&[25, 5, 50]))),
+            handle_oversized(20, items(&[25, 5, 50]), OversizePolicy::Reject)
+        );
+
+        assert_eq!(
+            Ok((items(&[5, 20]), vec![])),
+            handle_oversized(20, items(&[5, 20]), OversizePolicy::Reject)
+        );
+    }
+
+    #[test]
+    fn own_bin() {
+        let (fitting, bins) =
+            handle_oversized(20, items(&[50, 5, 20]), OversizePolicy::OwnBin).unwrap();
+
+        assert_eq!(items(&[5, 20]), fitting);
+        assert_eq!(vec![expected_test_bin(20, vec![50])], bins);
+        assert_eq!(30, bins[0].overflow());
+        assert!(bins[0].is_overflowing());
+    }
+
+    #[test]
+    fn split_fills_whole_bins() {
+        let (fitting, mut bins) =
+            handle_oversized(20, items(&[50, 5]), OversizePolicy::split(split)).unwrap();
+
+        assert_eq!(items(&[5, 10]), fitting);
+
+        bins.extend(first_fit_decreasing(20, fitting));
+        assert_eq!(
+            generate_test_bins(20, vec![vec![20], vec![20], vec![10, 5]]),
+            bins
+        );
+        assert!(bins.iter().all(|bin| !bin.is_overflowing()));
+    }
+
+    #[test]
+    fn split_with_closure() {
+        let bin_size = 20;
+        let policy = OversizePolicy::split(move |item: MyItem, size| {
+            // Leave some headroom in every part
+            split(item, size - bin_size / 10)
+        });
+
+        let (fitting, bins) = handle_oversized(bin_size, items(&[50]), policy).unwrap();
+
+        assert_eq!(items(&[14]), fitting);
+        assert_eq!(generate_test_bins(20, vec![vec![18], vec![18]]), bins);
+    }
+
+    #[test]
+    fn split_parts_that_are_too_large_again() {
+        // Halve the item, whatever the size of the bins
+        let policy = OversizePolicy::split(|item: MyItem, _| {
+            let half = item.size.div_ceil(2);
+            split(item, half)
+        });
+
+        let (fitting, bins) = handle_oversized(20, items(&[50]), policy).unwrap();
+
+        assert_eq!(items(&[12, 12]), fitting);
+        assert_eq!(generate_test_bins(20, vec![vec![13], vec![13]]), bins);
+        assert!(bins.iter().all(|bin| !bin.is_overflowing()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Splitting an item must leave parts that are smaller than the item")]
+    fn split_must_shrink_the_item() {
+        let policy = OversizePolicy::split(|item: MyItem, _| (MyItem { size: 0 }, item));
+
+        let _ = handle_oversized(20, items(&[50]), policy);
+    }
+
+    #[test]
+    fn zero_capacity() {
+        assert_eq!(
+            Err(PackError::ZeroCapacity),
+            handle_oversized(0, items(&[5]), OversizePolicy::OwnBin)
+        );
+    }
+}
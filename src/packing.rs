@@ -0,0 +1,263 @@
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+
+use crate::{Bin, Pack, Size};
+
+/// The result of a packing run: the bins, along with some statistics about how good the packing is.
+///
+/// Any `Vec` of bins returned by the packing algorithms can be turned into a `Packing`
+/// with [`Packing::new`] or [`From`].
+#[derive(Debug)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+pub struct Packing<T, S = usize> {
+    bins: Vec<Bin<T, S>>,
+}
+
+impl<T, S> Packing<T, S>
+where
+    S: Size,
+{
+    /// Wrap the bins returned by a packing algorithm.
+    pub fn new(bins: Vec<Bin<T, S>>) -> Self {
+        Self { bins }
+    }
+
+    /// Get the bins.
+    pub fn bins(&self) -> &[Bin<T, S>] {
+        &self.bins
+    }
+
+    /// Get the bins.
+    pub fn into_bins(self) -> Vec<Bin<T, S>> {
+        self.bins
+    }
+
+    /// Get the number of bins.
+    pub fn len(&self) -> usize {
+        self.bins.len()
+    }
+
+    /// Check whether there are no bins.
+    pub fn is_empty(&self) -> bool {
+        self.bins.is_empty()
+    }
+
+    /// Get the total remaining capacity of all bins.
+    pub fn total_waste(&self) -> S {
+        self.bins
+            .iter()
+            .fold(S::ZERO, |waste, bin| waste + bin.remaining_capacity)
+    }
+
+    /// Get the average [fill ratio](Bin::fill_ratio) of the bins, or 0 if there are no bins.
+    pub fn mean_fill_ratio(&self) -> f64 {
+        if self.bins.is_empty() {
+            return 0.0;
+        }
+
+        self.bins.iter().map(Bin::fill_ratio).sum::<f64>() / self.bins.len() as f64
+    }
+
+    /// Get the (population) variance of the [fill ratio](Bin::fill_ratio) of the bins,
+    /// or 0 if there are no bins.
+    pub fn fill_ratio_variance(&self) -> f64 {
+        if self.bins.is_empty() {
+            return 0.0;
+        }
+
+        let mean = self.mean_fill_ratio();
+        self.bins
+            .iter()
+            .map(|bin| (bin.fill_ratio() - mean).powi(2))
+            .sum::<f64>()
+            / self.bins.len() as f64
+    }
+
+    /// Get the [L1 lower bound](crate::bounds::l1) on the number of bins needed for the items.
+    ///
+    /// Every overflowing bin is counted as a bin of its own.
+    /// If the bins don't all have the same capacity, the biggest one is used.
+    pub fn lower_bound(&self) -> usize {
+        let Some(capacity) = self
+            .bins
+            .iter()
+            .map(|bin| bin.capacity)
+            .max_by(|a, b| a.total_cmp(b))
+        else {
+            return 0;
+        };
+
+        let (overflowing, used) =
+            self.bins
+                .iter()
+                .fold((0, S::ZERO), |(overflowing, used), bin| {
+                    if bin.is_overflowing() {
+                        (overflowing + 1, used)
+                    } else {
+                        (overflowing, used + bin.used())
+                    }
+                });
+
+        overflowing + used.div_ceil(capacity)
+    }
+
+    /// Get the number of bins divided by the [lower bound](Packing::lower_bound),
+    /// an upper bound on how far the packing is from the optimal one.
+    ///
+    /// A ratio of 1 means the packing is optimal.
+    pub fn approximation_ratio(&self) -> f64 {
+        match self.lower_bound() {
+            0 => 1.0,
+            lower_bound => self.bins.len() as f64 / lower_bound as f64,
+        }
+    }
+
+    /// Check that the items in every bin fit in its capacity.
+    ///
+    /// The sizes of the items are computed again, so this also catches bins
+    /// whose contents were changed (for example with [`Bin::map`]).
+    pub fn validate(&self) -> Result<(), OverCapacity<S>>
+    where
+        T: Pack<S>,
+    {
+        self.validate_by_key(T::size)
+    }
+
+    /// Check that the items in every bin fit in its capacity.
+    ///
+    /// Unlike [`validate`](Packing::validate), the items don't have to implement [`Pack`].
+    /// Instead, you need to provide a function that returns the size of the item.
+    pub fn validate_by_key<SizeFunc>(&self, key_func: SizeFunc) -> Result<(), OverCapacity<S>>
+    where
+        SizeFunc: Fn(&T) -> S,
+    {
+        for (bin_idx, bin) in self.bins.iter().enumerate() {
+            let used = bin
+                .contents
+                .iter()
+                .fold(S::ZERO, |used, item| used + key_func(item));
+
+            if used > bin.capacity {
+                return Err(OverCapacity {
+                    bin: bin_idx,
+                    used,
+                    capacity: bin.capacity,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T, S> From<Vec<Bin<T, S>>> for Packing<T, S>
+where
+    S: Size,
+{
+    fn from(bins: Vec<Bin<T, S>>) -> Self {
+        Self::new(bins)
+    }
+}
+
+impl<T, S> IntoIterator for Packing<T, S> {
+    type Item = Bin<T, S>;
+    type IntoIter = std::vec::IntoIter<Bin<T, S>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.bins.into_iter()
+    }
+}
+
+/// Error returned by [`Packing::validate`] when the items in a bin don't fit in its capacity.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct OverCapacity<S = usize> {
+    /// The index of the bin.
+    pub bin: usize,
+    /// The total size of the items in the bin.
+    pub used: S,
+    /// The capacity of the bin.
+    pub capacity: S,
+}
+
+impl<S> Display for OverCapacity<S>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "bin {} holds {:?}, but its capacity is {:?}",
+            self.bin, self.used, self.capacity
+        )
+    }
+}
+
+impl<S> Error for OverCapacity<S> where S: Debug {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offline::first_fit_decreasing::first_fit_decreasing;
+    use crate::online::first_fit::first_fit;
+    use crate::tests::{generate_test_bins, generate_test_set_a, MyItem};
+
+    #[test]
+    fn statistics() {
+        let (test_data, bin_size) = generate_test_set_a();
+
+        let packing = Packing::new(first_fit(bin_size, test_data));
+
+        // [1, 1, 1, 1, 3, 4], [10, 10], [10], [19], [19]
+        assert_eq!(5, packing.len());
+        assert_eq!(21, packing.total_waste());
+        assert_eq!(4, packing.lower_bound());
+        assert_eq!(1.25, packing.approximation_ratio());
+
+        let fill_ratios = [0.55, 1.0, 0.5, 0.95, 0.95];
+        let mean = fill_ratios.iter().sum::<f64>() / 5.0;
+        let variance = fill_ratios.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / 5.0;
+        assert!((packing.mean_fill_ratio() - mean).abs() < 1e-9);
+        assert!((packing.fill_ratio_variance() - variance).abs() < 1e-9);
+    }
+
+    #[test]
+    fn optimal_packing() {
+        let (test_data, bin_size) = generate_test_set_a();
+
+        let packing = Packing::from(first_fit_decreasing(bin_size, test_data));
+
+        assert_eq!(1.0, packing.approximation_ratio());
+        assert_eq!(Ok(()), packing.validate());
+    }
+
+    #[test]
+    fn empty_packing() {
+        let packing = Packing::<MyItem>::new(vec![]);
+
+        assert_eq!(0, packing.lower_bound());
+        assert_eq!(1.0, packing.approximation_ratio());
+        assert_eq!(0.0, packing.mean_fill_ratio());
+        assert_eq!(Ok(()), packing.validate());
+    }
+
+    #[test]
+    fn validate_recomputes_sizes() {
+        let bins = generate_test_bins(20, vec![vec![10, 10], vec![5]])
+            .into_iter()
+            .map(|bin| {
+                bin.map(|item| MyItem {
+                    size: item.size * 2,
+                })
+            })
+            .collect();
+
+        assert_eq!(
+            Err(OverCapacity {
+                bin: 0,
+                used: 40,
+                capacity: 20
+            }),
+            Packing::new(bins).validate()
+        );
+    }
+}
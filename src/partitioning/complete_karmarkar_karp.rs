@@ -0,0 +1,304 @@
+use std::time::Instant;
+
+use crate::offline::exact::{Budget, ExactSolution};
+use crate::{Pack, Size};
+
+use super::largest_differencing::{self, Partition, Subset};
+use super::{assert_valid_bin_count, into_bins};
+
+/// Partition items into `k` bins so that the largest bin is as small as possible,
+/// using the Complete Karmarkar-Karp algorithm by Korf.
+///
+/// This searches all ways of combining partitions in the [Largest differencing method](super::largest_differencing),
+/// trying the one the Largest differencing method would pick first.
+/// The search starts out with the partition the Largest differencing method finds, and only improves from there,
+/// so the `budget` can be used to stop the search early and still get a good partition;
+/// check [`ExactSolution::optimal`] to see whether the search was able to finish.
+///
+/// The worst case running time is exponential in the number of items, and grows quickly with `k` as well.
+///
+/// Panics if `k` is 0.
+pub fn complete_karmarkar_karp<T, S>(k: usize, items: Vec<T>, budget: Budget) -> ExactSolution<T, S>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    complete_karmarkar_karp_by_key(k, items, budget, T::size)
+}
+
+/// Partition items into `k` bins so that the largest bin is as small as possible,
+/// using the Complete Karmarkar-Karp algorithm by Korf.
+///
+/// Unlike [`complete_karmarkar_karp`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+pub fn complete_karmarkar_karp_by_key<T, S, SizeFunc>(
+    k: usize,
+    items: Vec<T>,
+    budget: Budget,
+    key_func: SizeFunc,
+) -> ExactSolution<T, S>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    assert_valid_bin_count(k);
+
+    let sizes = items.iter().map(key_func).collect::<Vec<_>>();
+
+    let mut solver = Solver {
+        k,
+        perfect: sizes
+            .iter()
+            .fold(S::ZERO, |total, &size| total + size)
+            .to_f64()
+            / k as f64,
+        best: largest_differencing::partition(k, &sizes),
+        budget,
+        started: Instant::now(),
+        nodes: 0,
+        exhausted: false,
+    };
+    solver.search(
+        sizes
+            .iter()
+            .enumerate()
+            .map(|(idx, &size)| Partition::single(k, idx, size))
+            .collect(),
+    );
+
+    let optimal = !solver.exhausted || solver.is_perfect();
+    let subsets = solver
+        .best
+        .subsets
+        .into_iter()
+        .map(|subset| subset.items)
+        .collect();
+
+    ExactSolution {
+        bins: into_bins(items, &sizes, subsets),
+        optimal,
+    }
+}
+
+struct Solver<S> {
+    k: usize,
+    /// The size of the largest bin if all bins had the same size, which is a lower bound.
+    perfect: f64,
+
+    /// The best partition so far, which starts out as the one the Largest differencing method finds.
+    best: Partition<S>,
+
+    budget: Budget,
+    started: Instant,
+    nodes: usize,
+    exhausted: bool,
+}
+
+impl<S> Solver<S>
+where
+    S: Size,
+{
+    fn search(&mut self, mut partitions: Vec<Partition<S>>) {
+        if self.exhausted || self.is_perfect() {
+            return;
+        }
+
+        self.nodes += 1;
+        if self.budget_exceeded() {
+            self.exhausted = true;
+            return;
+        }
+
+        if partitions.len() <= 1 {
+            if let Some(partition) = partitions.pop() {
+                if self.improves(largest(&partition)) {
+                    self.best = partition;
+                }
+            }
+            return;
+        }
+
+        // Subsets only get bigger, so the largest one so far is a lower bound
+        let lower_bound = partitions
+            .iter()
+            .map(largest)
+            .max_by(|a, b| a.total_cmp(b))
+            .expect("There are at least two partitions");
+        if !self.improves(lower_bound) {
+            return;
+        }
+
+        // Combine the two partitions with the largest difference, like the Largest differencing method
+        partitions.sort_by(|a, b| b.difference().total_cmp(&a.difference()));
+        let a = partitions.remove(0);
+        let b = partitions.remove(0);
+
+        let mut used = vec![false; self.k];
+        let mut combined = Vec::with_capacity(self.k);
+        self.combine(&a, &b, &mut used, &mut combined, &partitions);
+    }
+
+    /// Try every way of combining the subsets of `a` with the subsets of `b`,
+    /// where the first `combined.len()` subsets of `a` have already been combined.
+    fn combine(
+        &mut self,
+        a: &Partition<S>,
+        b: &Partition<S>,
+        used: &mut [bool],
+        combined: &mut Vec<Subset<S>>,
+        rest: &[Partition<S>],
+    ) {
+        let pos = combined.len();
+        if pos == self.k {
+            let mut partitions = rest.to_vec();
+            partitions.push(Partition::sorted(combined.clone()));
+            self.search(partitions);
+            return;
+        }
+
+        // The subsets of `a` are sorted by decreasing sum, so try the smallest subsets of `b` first
+        let mut tried = Vec::new();
+        for idx in (0..self.k).rev() {
+            if used[idx] || tried.contains(&b.subsets[idx].sum) {
+                continue;
+            }
+            tried.push(b.subsets[idx].sum);
+
+            let sum = a.subsets[pos].sum + b.subsets[idx].sum;
+            if !self.improves(sum) {
+                break;
+            }
+
+            used[idx] = true;
+            let mut items = a.subsets[pos].items.clone();
+            items.extend(&b.subsets[idx].items);
+            combined.push(Subset { sum, items });
+
+            self.combine(a, b, used, combined, rest);
+
+            combined.pop();
+            used[idx] = false;
+
+            if self.exhausted || self.is_perfect() {
+                return;
+            }
+        }
+    }
+
+    /// Check whether a partition with the given largest subset would be better than the best one so far.
+    fn improves(&self, largest: S) -> bool {
+        largest < self::largest(&self.best)
+    }
+
+    /// Check whether the best partition so far is perfectly balanced, so it can't be improved.
+    fn is_perfect(&self) -> bool {
+        largest(&self.best).to_f64() <= self.perfect
+    }
+
+    fn budget_exceeded(&self) -> bool {
+        if let Some(max_nodes) = self.budget.max_nodes {
+            if self.nodes > max_nodes {
+                return true;
+            }
+        }
+
+        // Checking the time is relatively expensive, so only do so every 1024 nodes
+        if let Some(max_duration) = self.budget.max_duration {
+            if self.nodes & 1023 == 0 && self.started.elapsed() >= max_duration {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// The sum of the largest subset of the partition.
+fn largest<S>(partition: &Partition<S>) -> S
+where
+    S: Size,
+{
+    partition.subsets[0].sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::partitioning::tests::{bin_sizes, items};
+    use crate::Bin;
+
+    #[test]
+    fn it_works() {
+        let result = complete_karmarkar_karp(2, items(&[4, 5, 6, 7, 8]), Budget::unlimited());
+
+        // The largest differencing method ends up with 16 and 14
+        assert!(result.optimal);
+        assert_eq!(vec![vec![4, 6, 5], vec![8, 7]], bin_sizes(&result.bins));
+    }
+
+    #[test]
+    fn three_way() {
+        let result =
+            complete_karmarkar_karp(3, items(&[5, 8, 6, 4, 7, 10, 9, 3]), Budget::unlimited());
+
+        assert!(result.optimal);
+        // 52 / 3 rounded up is 18
+        assert_eq!(
+            vec![18, 17, 17],
+            result.bins.iter().map(Bin::used).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn exhausted_budget_returns_largest_differencing() {
+        let result = complete_karmarkar_karp(
+            2,
+            items(&[4, 5, 6, 7, 8]),
+            Budget::unlimited().with_max_nodes(5),
+        );
+
+        assert!(!result.optimal);
+        assert_eq!(
+            vec![16, 14],
+            result.bins.iter().map(Bin::used).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn no_budget_returns_largest_differencing() {
+        let result = complete_karmarkar_karp(
+            2,
+            items(&[4, 5, 6, 7, 8]),
+            Budget::unlimited().with_max_nodes(0),
+        );
+
+        assert!(!result.optimal);
+        assert_eq!(
+            vec![16, 14],
+            result.bins.iter().map(Bin::used).collect::<Vec<_>>()
+        );
+        assert_eq!(5, result.bins.iter().map(Bin::len).sum::<usize>());
+    }
+
+    #[test]
+    fn returns_k_bins() {
+        let result = complete_karmarkar_karp(3, items(&[]), Budget::unlimited());
+
+        assert!(result.optimal);
+        assert_eq!(3, result.bins.len());
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let result = complete_karmarkar_karp_by_key(
+            2,
+            vec![3usize, 1, 1, 2, 2, 1],
+            Budget::unlimited(),
+            |&size| size,
+        );
+
+        assert!(result.optimal);
+        assert_eq!(5, result.bins[0].used());
+        assert_eq!(5, result.bins[1].used());
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{Bin, Pack, Size};
+
+use super::{assert_valid_bin_count, into_bins};
+
+/// Partition items into `k` bins using the [greedy](https://en.wikipedia.org/wiki/Greedy_number_partitioning)
+/// algorithm (also known as Longest-processing-time-first).
+///
+/// The items are sorted in decreasing order, and every item is put in the bin with the smallest sum so far.
+/// The largest bin is never more than `4/3` times as large as in the optimal partition.
+///
+/// Panics if `k` is 0.
+pub fn greedy<T, S>(k: usize, items: Vec<T>) -> Vec<Bin<T, S>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    greedy_by_key(k, items, T::size)
+}
+
+/// Partition items into `k` bins using the [greedy](https://en.wikipedia.org/wiki/Greedy_number_partitioning)
+/// algorithm (also known as Longest-processing-time-first).
+///
+/// Unlike [`greedy`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+pub fn greedy_by_key<T, S, SizeFunc>(k: usize, items: Vec<T>, key_func: SizeFunc) -> Vec<Bin<T, S>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    assert_valid_bin_count(k);
+
+    let sizes = items.iter().map(key_func).collect::<Vec<_>>();
+
+    // Go over the items in decreasing order
+    let mut order = (0..sizes.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| sizes[b].total_cmp(&sizes[a]));
+
+    let mut sums = vec![S::ZERO; k];
+    let mut subsets = vec![Vec::new(); k];
+    for idx in order {
+        let smallest = (0..k)
+            .min_by(|&a, &b| sums[a].total_cmp(&sums[b]))
+            .expect("There is at least one bin");
+
+        sums[smallest] = sums[smallest] + sizes[idx];
+        subsets[smallest].push(idx);
+    }
+
+    into_bins(items, &sizes, subsets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::partitioning::tests::{bin_sizes, items};
+
+    #[test]
+    fn it_works() {
+        let result = greedy(2, items(&[4, 5, 6, 7, 8]));
+
+        assert_eq!(vec![vec![8, 5, 4], vec![7, 6]], bin_sizes(&result));
+        assert_eq!(17, result[0].capacity());
+        assert_eq!(4, result[1].remaining_capacity());
+    }
+
+    #[test]
+    fn returns_k_bins() {
+        let result = greedy(3, items(&[5]));
+
+        assert_eq!(vec![vec![5], vec![], vec![]], bin_sizes(&result));
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let result = greedy_by_key(3, vec![1.5, 2.5, 1.0, 1.0], |&size| size);
+
+        assert_eq!(&[2.5], result[0].contents());
+        assert_eq!(&[1.5], result[1].contents());
+        assert_eq!(&[1.0, 1.0], result[2].contents());
+    }
+}
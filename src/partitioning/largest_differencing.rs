@@ -0,0 +1,221 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::{Bin, Pack, Size};
+
+use super::{assert_valid_bin_count, into_bins};
+
+/// Partition items into `k` bins using the [Largest differencing method](https://en.wikipedia.org/wiki/Largest_differencing_method)
+/// by Karmarkar and Karp.
+///
+/// Every item starts out as a partition of its own, with the item in one bin and the others empty.
+/// The two partitions with the largest difference between their largest and smallest bin
+/// are then repeatedly combined, putting the largest bin of one together with the smallest bin of the other,
+/// until a single partition is left.
+///
+/// This usually gives better results than [`greedy`](super::greedy::greedy),
+/// especially when there are many items.
+///
+/// Panics if `k` is 0.
+pub fn largest_differencing<T, S>(k: usize, items: Vec<T>) -> Vec<Bin<T, S>>
+where
+    T: Pack<S>,
+    S: Size,
+{
+    largest_differencing_by_key(k, items, T::size)
+}
+
+/// Partition items into `k` bins using the [Largest differencing method](https://en.wikipedia.org/wiki/Largest_differencing_method)
+/// by Karmarkar and Karp.
+///
+/// Unlike [`largest_differencing`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+pub fn largest_differencing_by_key<T, S, SizeFunc>(
+    k: usize,
+    items: Vec<T>,
+    key_func: SizeFunc,
+) -> Vec<Bin<T, S>>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    assert_valid_bin_count(k);
+
+    let sizes = items.iter().map(key_func).collect::<Vec<_>>();
+    let subsets = subsets(k, &sizes);
+
+    into_bins(items, &sizes, subsets)
+}
+
+/// Partition the sizes into `k` subsets, returning the indices of the sizes in every subset.
+fn subsets<S>(k: usize, sizes: &[S]) -> Vec<Vec<usize>>
+where
+    S: Size,
+{
+    partition(k, sizes)
+        .subsets
+        .into_iter()
+        .map(|subset| subset.items)
+        .collect()
+}
+
+/// Partition the sizes into `k` subsets using the Largest differencing method.
+pub(crate) fn partition<S>(k: usize, sizes: &[S]) -> Partition<S>
+where
+    S: Size,
+{
+    let mut heap = sizes
+        .iter()
+        .enumerate()
+        .map(|(idx, &size)| Partition::single(k, idx, size))
+        .collect::<BinaryHeap<_>>();
+
+    while heap.len() > 1 {
+        let a = heap.pop().expect("Heap has at least two partitions");
+        let b = heap.pop().expect("Heap has at least two partitions");
+        heap.push(a.combine(b));
+    }
+
+    heap.pop().unwrap_or_else(|| Partition::empty(k))
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Subset<S> {
+    pub(crate) sum: S,
+    pub(crate) items: Vec<usize>,
+}
+
+/// A partition of some of the items into `k` subsets, sorted by decreasing sum.
+#[derive(Debug, Clone)]
+pub(crate) struct Partition<S> {
+    pub(crate) subsets: Vec<Subset<S>>,
+}
+
+impl<S> Partition<S>
+where
+    S: Size,
+{
+    /// A partition with `k` empty subsets.
+    pub(crate) fn empty(k: usize) -> Self {
+        Self {
+            subsets: vec![
+                Subset {
+                    sum: S::ZERO,
+                    items: Vec::new(),
+                };
+                k
+            ],
+        }
+    }
+
+    /// A partition with a single item in the first subset.
+    pub(crate) fn single(k: usize, idx: usize, size: S) -> Self {
+        let mut partition = Self::empty(k);
+        partition.subsets[0] = Subset {
+            sum: size,
+            items: vec![idx],
+        };
+
+        partition
+    }
+
+    /// The difference between the largest and the smallest subset.
+    pub(crate) fn difference(&self) -> S {
+        self.subsets[0].sum - self.subsets[self.subsets.len() - 1].sum
+    }
+
+    /// Combine two partitions, putting the largest subset of one with the smallest subset of the other.
+    fn combine(self, other: Self) -> Self {
+        let subsets = self
+            .subsets
+            .into_iter()
+            .zip(other.subsets.into_iter().rev())
+            .map(|(mut a, b)| {
+                a.sum = a.sum + b.sum;
+                a.items.extend(b.items);
+                a
+            })
+            .collect();
+
+        Self::sorted(subsets)
+    }
+
+    /// Create a partition from subsets in any order.
+    pub(crate) fn sorted(mut subsets: Vec<Subset<S>>) -> Self {
+        subsets.sort_by(|a, b| b.sum.total_cmp(&a.sum));
+        Self { subsets }
+    }
+}
+
+impl<S> PartialEq for Partition<S>
+where
+    S: Size,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl<S> Eq for Partition<S> where S: Size {}
+
+impl<S> PartialOrd for Partition<S>
+where
+    S: Size,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Partitions are ordered by their difference.
+impl<S> Ord for Partition<S>
+where
+    S: Size,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.difference().total_cmp(&other.difference())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::partitioning::tests::{bin_sizes, items};
+
+    #[test]
+    fn it_works() {
+        let result = largest_differencing(2, items(&[4, 5, 6, 7, 8]));
+
+        // The greedy algorithm ends up with 17 and 13
+        let mut sums = result.iter().map(Bin::used).collect::<Vec<_>>();
+        sums.sort();
+        assert_eq!(vec![14, 16], sums);
+    }
+
+    #[test]
+    fn three_way() {
+        let result = largest_differencing(3, items(&[8, 7, 6, 5, 4]));
+
+        assert_eq!(vec![vec![5, 6], vec![4, 7], vec![8]], bin_sizes(&result));
+    }
+
+    #[test]
+    fn returns_k_bins() {
+        assert_eq!(
+            vec![vec![5], vec![], vec![]],
+            bin_sizes(&largest_differencing(3, items(&[5])))
+        );
+        assert_eq!(
+            vec![Vec::<usize>::new(); 2],
+            bin_sizes(&largest_differencing(2, items(&[])))
+        );
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let result = largest_differencing_by_key(2, vec![3.0, 1.0, 2.0], |&size| size);
+
+        assert_eq!(3.0, result[0].used());
+        assert_eq!(3.0, result[1].used());
+    }
+}
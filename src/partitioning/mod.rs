@@ -0,0 +1,66 @@
+//! [Multiway number partitioning](https://en.wikipedia.org/wiki/Multiway_number_partitioning),
+//! where items are split into a fixed number of bins so that the largest bin is as small as possible.
+//!
+//! This is the inverse of bin packing: instead of fixing the size of the bins
+//! and minimizing their number, the number of bins is fixed and their size is minimized.
+//!
+//! Every algorithm returns exactly `k` bins.
+//! The capacity of every bin is set to the sum of the largest bin,
+//! so the [remaining capacity](crate::Bin::remaining_capacity) of a bin is how far it is from the largest one.
+
+pub mod complete_karmarkar_karp;
+pub mod greedy;
+pub mod largest_differencing;
+
+use crate::{Bin, Size};
+
+/// Panics if there are no bins to partition the items into.
+fn assert_valid_bin_count(k: usize) {
+    assert!(k > 0, "Number of bins must be greater than 0");
+}
+
+/// Put the items in bins, given the indices of the items that belong in every bin.
+fn into_bins<T, S>(items: Vec<T>, sizes: &[S], subsets: Vec<Vec<usize>>) -> Vec<Bin<T, S>>
+where
+    S: Size,
+{
+    let sums = subsets
+        .iter()
+        .map(|subset| subset.iter().fold(S::ZERO, |sum, &idx| sum + sizes[idx]))
+        .collect::<Vec<_>>();
+    let capacity = sums
+        .iter()
+        .copied()
+        .max_by(|a, b| a.total_cmp(b))
+        .unwrap_or(S::ZERO);
+
+    let mut items = items.into_iter().map(Some).collect::<Vec<_>>();
+
+    subsets
+        .into_iter()
+        .map(|subset| {
+            let mut bin = Bin::with_capacity(capacity);
+            for idx in subset {
+                let item = items[idx].take().expect("Item is in more than one bin");
+                bin.add_with_size(item, sizes[idx]);
+            }
+            bin
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::tests::MyItem;
+    use crate::Bin;
+
+    pub fn items(sizes: &[usize]) -> Vec<MyItem> {
+        sizes.iter().map(|&size| MyItem { size }).collect()
+    }
+
+    pub fn bin_sizes(bins: &[Bin<MyItem>]) -> Vec<Vec<usize>> {
+        bins.iter()
+            .map(|bin| bin.iter().map(|item| item.size).collect())
+            .collect()
+    }
+}
@@ -0,0 +1,132 @@
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::ops::{Add, Sub};
+
+/// A type that can be used as the size of items and bins.
+///
+/// This is implemented for all unsigned integer types and for `f32` and `f64`.
+/// `usize` is the default everywhere, so if your sizes are `usize` you never have to mention this trait.
+///
+/// To use fixed-point or other custom sizes, implement this trait for your own type.
+pub trait Size: Copy + Debug + PartialOrd + Add<Output = Self> + Sub<Output = Self> {
+    /// The size of nothing at all.
+    const ZERO: Self;
+
+    /// Subtract `other`, stopping at zero instead of going negative or overflowing.
+    fn saturating_sub(self, other: Self) -> Self;
+
+    /// Add `other`, returning `None` if the result can't be represented.
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// A total ordering, used to sort items by their size.
+    fn total_cmp(&self, other: &Self) -> Ordering;
+
+    /// How many times `other` fits in `self`, rounded down.
+    ///
+    /// Saturates at `usize::MAX`, and `other` is never zero.
+    fn div_floor(self, other: Self) -> usize;
+
+    /// How many times `other` fits in `self`, rounded up.
+    /// This is how many bins of size `other` are needed to hold a total size of `self`.
+    ///
+    /// Saturates at `usize::MAX`, and `other` is never zero.
+    fn div_ceil(self, other: Self) -> usize;
+
+    /// Convert the size to a floating point number, for example to compute ratios.
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_size_for_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl Size for $ty {
+                const ZERO: Self = 0;
+
+                fn saturating_sub(self, other: Self) -> Self {
+                    <$ty>::saturating_sub(self, other)
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$ty>::checked_add(self, other)
+                }
+
+                fn total_cmp(&self, other: &Self) -> Ordering {
+                    Ord::cmp(self, other)
+                }
+
+                fn div_floor(self, other: Self) -> usize {
+                    usize::try_from(self / other).unwrap_or(usize::MAX)
+                }
+
+                fn div_ceil(self, other: Self) -> usize {
+                    usize::try_from(<$ty>::div_ceil(self, other)).unwrap_or(usize::MAX)
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_size_for_unsigned!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_size_for_float {
+    ($($ty:ty),*) => {
+        $(
+            impl Size for $ty {
+                const ZERO: Self = 0.0;
+
+                fn saturating_sub(self, other: Self) -> Self {
+                    (self - other).max(0.0)
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    Some(self + other).filter(|sum| sum.is_finite())
+                }
+
+                fn total_cmp(&self, other: &Self) -> Ordering {
+                    <$ty>::total_cmp(self, other)
+                }
+
+                fn div_floor(self, other: Self) -> usize {
+                    (self / other).floor() as usize
+                }
+
+                fn div_ceil(self, other: Self) -> usize {
+                    (self / other).ceil() as usize
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_size_for_float!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_sizes() {
+        assert_eq!(0, Size::saturating_sub(3u8, 5));
+        assert_eq!(None, Size::checked_add(u8::MAX, 1));
+        assert_eq!(3, Size::div_floor(20u64, 6));
+        assert_eq!(4, Size::div_ceil(20u64, 6));
+        assert_eq!(usize::MAX, Size::div_floor(u128::MAX, 1));
+    }
+
+    #[test]
+    fn float_sizes() {
+        assert_eq!(0.0, Size::saturating_sub(0.5, 1.5));
+        assert_eq!(None, Size::checked_add(f64::MAX, f64::MAX));
+        assert_eq!(3, Size::div_floor(2.0, 0.6));
+        assert_eq!(4, Size::div_ceil(2.0, 0.6));
+        assert_eq!(Ordering::Less, Size::total_cmp(&0.5, &1.5));
+    }
+}
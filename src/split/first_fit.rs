@@ -0,0 +1,156 @@
+use crate::{Bin, Pack, Size};
+
+use super::{pack, Fragment, Split};
+
+/// Pack splittable items in bins using [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing),
+/// splitting items to fill up bins completely.
+///
+/// Every item is put in the first bin that has room for all of it.
+/// If a bin before that one can be filled up with a fragment of the item instead,
+/// the item is split, and the rest of it is packed the same way.
+/// Items that are larger than a bin are split over multiple bins.
+///
+/// Items are only split if both parts are at least [`Split::min_fragment_size`],
+/// so bins aren't always filled up completely.
+/// An item that can't be split to fit in an empty bin is put in a bin of its own, sticking out of it.
+///
+/// Panics if `bin_size` is 0.
+pub fn first_fit<T, S>(bin_size: S, items: impl IntoIterator<Item = T>) -> Vec<Bin<Fragment<T>, S>>
+where
+    T: Pack<S> + Split<S>,
+    S: Size,
+{
+    first_fit_by_key(bin_size, items, T::size)
+}
+
+/// Pack splittable items in bins using [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing),
+/// splitting items to fill up bins completely.
+///
+/// Unlike [`first_fit`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+/// It is only called on the items that are given, not on their fragments.
+pub fn first_fit_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: impl IntoIterator<Item = T>,
+    key_func: SizeFunc,
+) -> Vec<Bin<Fragment<T>, S>>
+where
+    T: Split<S>,
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    pack(
+        bin_size,
+        items
+            .into_iter()
+            .enumerate()
+            .map(|(source, item)| (source, key_func(&item), item)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::split::tests::{bin_fragments, files};
+
+    #[test]
+    fn it_works() {
+        let result = first_fit(10, files(&[('a', 7), ('b', 7), ('c', 7)], 1, 0));
+
+        assert_eq!(
+            vec![
+                vec![('a', 7, 0), ('b', 3, 0)],
+                vec![('b', 4, 1), ('c', 6, 0)],
+                vec![('c', 1, 1)],
+            ],
+            bin_fragments(&result)
+        );
+        assert!(result[..2].iter().all(|bin| bin.remaining_capacity() == 0));
+    }
+
+    #[test]
+    fn tracks_fragments() {
+        let result = first_fit(10, files(&[('a', 4), ('b', 25)], 1, 0));
+
+        let b = result
+            .iter()
+            .flatten()
+            .filter(|fragment| fragment.source == 1)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![(6, 0), (10, 1), (9, 2)],
+            b.iter()
+                .map(|fragment| (fragment.item.size, fragment.part))
+                .collect::<Vec<_>>()
+        );
+        assert!(b.iter().all(|fragment| fragment.split));
+
+        let a = &result[0].contents()[0];
+        assert_eq!((0, 0, false), (a.source, a.part, a.split));
+    }
+
+    #[test]
+    fn fragments_have_overhead() {
+        let result = first_fit(10, files(&[('a', 7), ('b', 7), ('c', 7)], 1, 1));
+
+        // Every fragment of a split file needs a header of 1
+        assert_eq!(
+            vec![
+                vec![('a', 7, 0), ('b', 2, 0)],
+                vec![('b', 5, 1), ('c', 3, 0)],
+                vec![('c', 4, 1)],
+            ],
+            bin_fragments(&result)
+        );
+        assert_eq!(
+            vec![10, 10, 5],
+            result.iter().map(Bin::used).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn respects_min_fragment_size() {
+        // Splitting `b` into 2 and 5 would leave a fragment that is too small
+        let result = first_fit(10, files(&[('a', 8), ('b', 7)], 3, 0));
+        assert_eq!(
+            vec![vec![('a', 8, 0)], vec![('b', 7, 0)]],
+            bin_fragments(&result)
+        );
+
+        // The rest of `b` can't be smaller than 3 either
+        let result = first_fit(10, files(&[('a', 6), ('b', 6)], 3, 0));
+        assert_eq!(
+            vec![vec![('a', 6, 0)], vec![('b', 6, 0)]],
+            bin_fragments(&result)
+        );
+        let result = first_fit(10, files(&[('a', 6), ('b', 7)], 3, 0));
+        assert_eq!(
+            vec![vec![('a', 6, 0), ('b', 4, 0)], vec![('b', 3, 1)]],
+            bin_fragments(&result)
+        );
+    }
+
+    #[test]
+    fn unsplittable_oversized_items_get_their_own_bin() {
+        // The header takes up the whole bin, so no fragment fits
+        let result = first_fit(10, files(&[('a', 3), ('b', 12)], 1, 10));
+
+        assert_eq!(
+            vec![vec![('a', 3, 0)], vec![('b', 12, 0)]],
+            bin_fragments(&result)
+        );
+        assert_eq!(2, result[1].overflow());
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let files = files(&[('a', 7), ('b', 7), ('c', 7)], 1, 0);
+
+        let result = first_fit_by_key(10, files, |file| file.size);
+
+        assert_eq!(
+            vec![10, 10, 1],
+            result.iter().map(Bin::used).collect::<Vec<_>>()
+        );
+    }
+}
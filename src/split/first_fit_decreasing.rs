@@ -0,0 +1,94 @@
+use crate::{Bin, Pack, Size};
+
+use super::{pack, Fragment, Split};
+
+/// Pack splittable items in bins using [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing),
+/// splitting items to fill up bins completely.
+///
+/// The items are sorted in decreasing order, and then packed like [`first_fit`](super::first_fit::first_fit).
+/// Packing the large items first means the bins are mostly filled up by splitting the smaller items.
+///
+/// Panics if `bin_size` is 0.
+pub fn first_fit_decreasing<T, S>(bin_size: S, items: Vec<T>) -> Vec<Bin<Fragment<T>, S>>
+where
+    T: Pack<S> + Split<S>,
+    S: Size,
+{
+    first_fit_decreasing_by_key(bin_size, items, T::size)
+}
+
+/// Pack splittable items in bins using [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing),
+/// splitting items to fill up bins completely.
+///
+/// Unlike [`first_fit_decreasing`], the items don't have to implement [`Pack`].
+/// Instead, you need to provide a function that returns the size of the item.
+/// It is only called on the items that are given, not on their fragments.
+pub fn first_fit_decreasing_by_key<T, S, SizeFunc>(
+    bin_size: S,
+    items: Vec<T>,
+    key_func: SizeFunc,
+) -> Vec<Bin<Fragment<T>, S>>
+where
+    T: Split<S>,
+    S: Size,
+    SizeFunc: Fn(&T) -> S,
+{
+    let mut items = items
+        .into_iter()
+        .enumerate()
+        .map(|(source, item)| (source, key_func(&item), item))
+        .collect::<Vec<_>>();
+    items.sort_by(|(_, a, _), (_, b, _)| b.total_cmp(a));
+
+    pack(bin_size, items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::split::tests::{bin_fragments, files};
+
+    #[test]
+    fn it_works() {
+        let result = first_fit_decreasing(10, files(&[('a', 3), ('b', 9), ('c', 6)], 1, 0));
+
+        assert_eq!(
+            vec![
+                vec![('b', 9, 0), ('c', 1, 0)],
+                vec![('c', 5, 1), ('a', 3, 0)]
+            ],
+            bin_fragments(&result)
+        );
+        assert_eq!(
+            vec![(1, 0), (2, 0), (2, 1), (0, 0)],
+            result
+                .iter()
+                .flatten()
+                .map(|fragment| (fragment.source, fragment.part))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn fills_bins_completely() {
+        let sizes = [('a', 13), ('b', 4), ('c', 8), ('d', 21), ('e', 2), ('f', 9)];
+
+        let result = first_fit_decreasing(10, files(&sizes, 1, 0));
+
+        // 57 in total needs at least 6 bins
+        assert_eq!(6, result.len());
+        assert!(result[..5].iter().all(|bin| bin.remaining_capacity() == 0));
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let files = files(&[('a', 3), ('b', 9), ('c', 6)], 1, 0);
+
+        let result = first_fit_decreasing_by_key(10, files, |file| file.size);
+
+        assert_eq!(
+            vec![10, 8],
+            result.iter().map(Bin::used).collect::<Vec<_>>()
+        );
+    }
+}
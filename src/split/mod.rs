@@ -0,0 +1,209 @@
+//! Packing of splittable items, like files that can be sent in chunks.
+//!
+//! Items that implement [`Split`] can be cut into fragments to fill up bins completely.
+//! Every bin holds [`Fragment`]s, which record which item they came from.
+//!
+//! Splitting can come at a cost: every fragment of an item that was split
+//! takes up [`Split::overhead`] on top of its own size, for example for a header.
+//! An item that isn't split doesn't have any overhead.
+
+pub mod first_fit;
+pub mod first_fit_decreasing;
+
+use crate::{Bin, Size};
+
+/// An item that can be split into smaller fragments.
+///
+/// The size of an item comes from [`crate::Pack`], or from the function passed to one of the `_by_key` functions.
+pub trait Split<S = usize>: Sized
+where
+    S: Size,
+{
+    /// Split the item into a fragment of exactly `size` and the rest of the item.
+    ///
+    /// This is only called with a `size` that is larger than 0 and smaller than the size of the item,
+    /// and that leaves at least [`min_fragment_size`](Split::min_fragment_size) for both parts.
+    /// The sizes are the same as those the item was packed with, so those from the key function if one was used.
+    fn split(self, size: S) -> (Self, Self);
+
+    /// The smallest size a fragment may have.
+    fn min_fragment_size(&self) -> S {
+        S::ZERO
+    }
+
+    /// How much space every fragment takes up on top of its size, once the item is split.
+    fn overhead(&self) -> S {
+        S::ZERO
+    }
+}
+
+/// A fragment of an item, or a whole item if it wasn't split.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Fragment<T> {
+    pub item: T,
+    /// The position of the item that this fragment came from, in the items that were packed.
+    pub source: usize,
+    /// Which fragment of that item this is, counting from 0.
+    pub part: usize,
+    /// Whether the item was split. If not, this is the whole item.
+    pub split: bool,
+}
+
+/// Find the size of a fragment that would fill up a bin with the given `remaining_capacity`,
+/// or `None` if the item can't be split that way.
+fn fragment_size<T, S>(item: &T, size: S, remaining_capacity: S) -> Option<S>
+where
+    T: Split<S>,
+    S: Size,
+{
+    let min_fragment_size = item.min_fragment_size();
+    let fragment_size = remaining_capacity.saturating_sub(item.overhead());
+
+    let valid = fragment_size > S::ZERO
+        && fragment_size < size
+        && fragment_size >= min_fragment_size
+        && size - fragment_size >= min_fragment_size;
+
+    valid.then_some(fragment_size)
+}
+
+/// Put the items in bins in the given order, splitting them to fill up the first bin with room left.
+///
+/// Every item goes in the first bin that either holds all of it or can be filled up with a fragment of it,
+/// and then the rest of the item goes on in the same way.
+fn pack<T, S>(
+    bin_size: S,
+    items: impl IntoIterator<Item = (usize, S, T)>,
+) -> Vec<Bin<Fragment<T>, S>>
+where
+    T: Split<S>,
+    S: Size,
+{
+    assert!(bin_size > S::ZERO, "Bin size must be greater than 0");
+
+    let mut bins = Vec::<Bin<Fragment<T>, S>>::new();
+
+    for (source, mut size, mut item) in items {
+        let mut part = 0;
+
+        loop {
+            // Once an item is split, every fragment has overhead
+            let split = part > 0;
+            let cost = if split { size + item.overhead() } else { size };
+
+            let found = bins.iter().enumerate().find_map(|(bin_idx, bin)| {
+                if cost <= bin.remaining_capacity {
+                    Some((bin_idx, None))
+                } else {
+                    fragment_size(&item, size, bin.remaining_capacity)
+                        .map(|fragment_size| (bin_idx, Some(fragment_size)))
+                }
+            });
+
+            let (bin_idx, fragment_size) = match found {
+                Some(found) => found,
+                None => {
+                    // Items that don't fit in an empty bin are still split, if possible
+                    let fragment_size = (cost > bin_size)
+                        .then(|| fragment_size(&item, size, bin_size))
+                        .flatten();
+                    bins.push(Bin::with_capacity(bin_size));
+                    (bins.len() - 1, fragment_size)
+                }
+            };
+
+            let Some(fragment_size) = fragment_size else {
+                bins[bin_idx].add_with_size(
+                    Fragment {
+                        item,
+                        source,
+                        part,
+                        split,
+                    },
+                    cost,
+                );
+                break;
+            };
+
+            let overhead = item.overhead();
+            let (fragment, rest) = item.split(fragment_size);
+            bins[bin_idx].add_with_size(
+                Fragment {
+                    item: fragment,
+                    source,
+                    part,
+                    split: true,
+                },
+                fragment_size + overhead,
+            );
+
+            item = rest;
+            size = size - fragment_size;
+            part += 1;
+        }
+    }
+
+    bins
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::Pack;
+
+    /// A dummy struct for testing, like a file that can be sent in chunks.
+    #[derive(Debug, Clone, Eq, PartialEq)]
+    pub struct MyFile {
+        pub name: char,
+        pub size: usize,
+        pub min_chunk: usize,
+        pub header: usize,
+    }
+
+    impl Pack for MyFile {
+        fn size(&self) -> usize {
+            self.size
+        }
+    }
+
+    impl Split for MyFile {
+        fn split(self, size: usize) -> (Self, Self) {
+            let rest = Self {
+                size: self.size - size,
+                ..self.clone()
+            };
+            (Self { size, ..self }, rest)
+        }
+
+        fn min_fragment_size(&self) -> usize {
+            self.min_chunk
+        }
+
+        fn overhead(&self) -> usize {
+            self.header
+        }
+    }
+
+    pub fn files(sizes: &[(char, usize)], min_chunk: usize, header: usize) -> Vec<MyFile> {
+        sizes
+            .iter()
+            .map(|&(name, size)| MyFile {
+                name,
+                size,
+                min_chunk,
+                header,
+            })
+            .collect()
+    }
+
+    /// The name, size and part of every fragment in every bin.
+    pub fn bin_fragments(bins: &[Bin<Fragment<MyFile>>]) -> Vec<Vec<(char, usize, usize)>> {
+        bins.iter()
+            .map(|bin| {
+                bin.iter()
+                    .map(|fragment| (fragment.item.name, fragment.item.size, fragment.part))
+                    .collect()
+            })
+            .collect()
+    }
+}
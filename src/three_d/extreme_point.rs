@@ -0,0 +1,347 @@
+use std::ops::Mul;
+
+use crate::{Bin, Size};
+
+use super::{Dimensions, Orientations, PackBox, Placement};
+
+/// Pack boxes in containers using the Extreme-point heuristic by Crainic, Perboli and Tadei.
+///
+/// The boxes are sorted by decreasing volume, and every box is put in the first container it fits in.
+/// Every container keeps a list of extreme points: the corners of the boxes placed so far,
+/// pushed back towards the origin until they touch another box or a wall.
+/// A box is put at the lowest extreme point where it fits (then the one furthest back, then the leftmost),
+/// in the first of its `orientations` that fits there.
+///
+/// Boxes that don't fit in a container at all, in any of their orientations,
+/// are put in a container of their own at the origin, sticking out of it.
+///
+/// Panics if the width, height or depth of the `container` is 0.
+pub fn extreme_point<T, S>(
+    container: Dimensions<S>,
+    orientations: Orientations,
+    items: Vec<T>,
+) -> Vec<Bin<Placement<T, S>, S>>
+where
+    T: PackBox<S>,
+    S: Size + Mul<Output = S>,
+{
+    extreme_point_by_key(container, orientations, items, T::dimensions)
+}
+
+/// Pack boxes in containers using the Extreme-point heuristic by Crainic, Perboli and Tadei.
+///
+/// Unlike [`extreme_point`], the items don't have to implement [`PackBox`].
+/// Instead, you need to provide a function that returns the dimensions of the item.
+pub fn extreme_point_by_key<T, S, SizeFunc>(
+    container: Dimensions<S>,
+    orientations: Orientations,
+    items: Vec<T>,
+    key_func: SizeFunc,
+) -> Vec<Bin<Placement<T, S>, S>>
+where
+    S: Size + Mul<Output = S>,
+    SizeFunc: Fn(&T) -> Dimensions<S>,
+{
+    assert!(
+        container.width > S::ZERO && container.height > S::ZERO && container.depth > S::ZERO,
+        "Container size must be greater than 0"
+    );
+
+    // Sort the items by decreasing volume
+    let mut items = items
+        .into_iter()
+        .map(|item| (key_func(&item), item))
+        .collect::<Vec<_>>();
+    items.sort_by(|(a, _), (b, _)| b.volume().total_cmp(&a.volume()));
+
+    // Containers that hold a box that doesn't fit have no extreme points to keep track of
+    let mut containers = Vec::<(Option<Container<S>>, Bin<Placement<T, S>, S>)>::new();
+
+    for (size, item) in items {
+        let placed = containers.iter_mut().find_map(|(free, bin)| {
+            let (position, placed_size) = free.as_mut()?.insert(size, orientations)?;
+            Some((bin, position, placed_size))
+        });
+
+        let (bin, position, placed_size) = match placed {
+            Some(placed) => placed,
+            None => {
+                let mut free = Container::new(container);
+                let placed = free.insert(size, orientations);
+                containers.push((
+                    placed.is_some().then_some(free),
+                    Bin::with_capacity(container.volume()),
+                ));
+
+                let (position, placed_size) = placed.unwrap_or(([S::ZERO; 3], size));
+                let (_, bin) = containers.last_mut().expect("A container was just added");
+                (bin, position, placed_size)
+            }
+        };
+
+        let [x, y, z] = position;
+        bin.add_with_size(
+            Placement {
+                item,
+                x,
+                y,
+                z,
+                width: placed_size.width,
+                height: placed_size.height,
+                depth: placed_size.depth,
+                rotated: placed_size != size,
+            },
+            size.volume(),
+        );
+    }
+
+    containers.into_iter().map(|(_, bin)| bin).collect()
+}
+
+/// A box in a container, as its lowest and highest corner.
+#[derive(Debug, Clone, Copy)]
+struct Cuboid<S> {
+    min: [S; 3],
+    max: [S; 3],
+}
+
+impl<S> Cuboid<S>
+where
+    S: Size,
+{
+    fn new(position: [S; 3], size: Dimensions<S>) -> Self {
+        let [x, y, z] = position;
+        Self {
+            min: position,
+            max: [x + size.width, y + size.height, z + size.depth],
+        }
+    }
+
+    fn intersects(&self, other: &Self) -> bool {
+        (0..3).all(|axis| self.min[axis] < other.max[axis] && other.min[axis] < self.max[axis])
+    }
+
+    /// Check whether a point lies inside this box, or on its lower faces.
+    fn contains(&self, point: &[S; 3]) -> bool {
+        (0..3).all(|axis| self.min[axis] <= point[axis] && point[axis] < self.max[axis])
+    }
+}
+
+/// The boxes in a container, and the extreme points where new boxes can be put.
+#[derive(Debug)]
+struct Container<S> {
+    size: Dimensions<S>,
+    boxes: Vec<Cuboid<S>>,
+    /// Sorted by height, then depth, then width.
+    extreme_points: Vec<[S; 3]>,
+}
+
+impl<S> Container<S>
+where
+    S: Size,
+{
+    fn new(size: Dimensions<S>) -> Self {
+        Self {
+            size,
+            boxes: Vec::new(),
+            extreme_points: vec![[S::ZERO; 3]],
+        }
+    }
+
+    /// Find a place for a box and put it there,
+    /// returning its position and the orientation it was placed in,
+    /// or `None` if it doesn't fit.
+    fn insert(
+        &mut self,
+        size: Dimensions<S>,
+        orientations: Orientations,
+    ) -> Option<([S; 3], Dimensions<S>)> {
+        let orientations = orientations.of(size);
+
+        let (point_idx, placed_size) =
+            self.extreme_points
+                .iter()
+                .enumerate()
+                .find_map(|(point_idx, point)| {
+                    orientations
+                        .iter()
+                        .find(|&&size| self.fits(point, size))
+                        .map(|&size| (point_idx, size))
+                })?;
+
+        let position = self.extreme_points.remove(point_idx);
+        self.add(Cuboid::new(position, placed_size));
+
+        Some((position, placed_size))
+    }
+
+    /// Check whether a box fits at the given position, inside the container and without overlapping other boxes.
+    fn fits(&self, position: &[S; 3], size: Dimensions<S>) -> bool {
+        let [x, y, z] = *position;
+        let inside = size.width <= self.size.width - x
+            && size.height <= self.size.height - y
+            && size.depth <= self.size.depth - z;
+        if !inside {
+            return false;
+        }
+
+        let cuboid = Cuboid::new(*position, size);
+        !self.boxes.iter().any(|other| other.intersects(&cuboid))
+    }
+
+    /// Add a box, and update the extreme points.
+    fn add(&mut self, cuboid: Cuboid<S>) {
+        self.boxes.push(cuboid);
+        self.extreme_points.retain(|point| !cuboid.contains(point));
+
+        // Every corner of the box next to one of its faces is pushed back along the two other axes
+        let [x, y, z] = cuboid.min;
+        let [right, top, front] = cuboid.max;
+        let corners = [([right, y, z], 0), ([x, top, z], 1), ([x, y, front], 2)];
+
+        for (corner, axis) in corners {
+            for project_axis in (0..3).filter(|&project_axis| project_axis != axis) {
+                let point = self.project(corner, project_axis);
+                let inside = point[0] < self.size.width
+                    && point[1] < self.size.height
+                    && point[2] < self.size.depth;
+                if inside && !self.extreme_points.contains(&point) {
+                    self.extreme_points.push(point);
+                }
+            }
+        }
+
+        self.extreme_points.sort_by(|a, b| {
+            a[1].total_cmp(&b[1])
+                .then_with(|| a[2].total_cmp(&b[2]))
+                .then_with(|| a[0].total_cmp(&b[0]))
+        });
+    }
+
+    /// Move a point towards the origin along `axis`, until it touches a box or the wall of the container.
+    fn project(&self, mut point: [S; 3], axis: usize) -> [S; 3] {
+        let others = [(axis + 1) % 3, (axis + 2) % 3];
+
+        point[axis] = self
+            .boxes
+            .iter()
+            .filter(|cuboid| {
+                cuboid.max[axis] <= point[axis]
+                    && others.iter().all(|&other| {
+                        cuboid.min[other] <= point[other] && point[other] < cuboid.max[other]
+                    })
+            })
+            .map(|cuboid| cuboid.max[axis])
+            .fold(
+                S::ZERO,
+                |highest, face| {
+                    if face > highest {
+                        face
+                    } else {
+                        highest
+                    }
+                },
+            );
+
+        point
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::random_numbers;
+
+    /// Pseudo-random boxes between 1 and `max` in every dimension.
+    fn random_boxes(count: usize, max: usize) -> Vec<Dimensions> {
+        let mut numbers = random_numbers(7, max);
+        let mut next = move || numbers.next().expect("There are always more numbers") + 1;
+
+        (0..count)
+            .map(|_| Dimensions::new(next(), next(), next()))
+            .collect()
+    }
+
+    #[test]
+    fn it_works() {
+        let container: Dimensions = Dimensions::new(10, 10, 10);
+        let items = vec![
+            Dimensions::new(10, 5, 10),
+            Dimensions::new(5, 5, 5),
+            Dimensions::new(10, 5, 5),
+        ];
+
+        let result = extreme_point(container, Orientations::Fixed, items);
+
+        assert_eq!(1, result.len());
+        assert_eq!(
+            vec![(0, 0, 0), (0, 5, 0), (0, 5, 5)],
+            result[0]
+                .iter()
+                .map(|placement| (placement.x, placement.y, placement.z))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(125, result[0].remaining_capacity());
+    }
+
+    #[test]
+    fn turns_boxes_to_fit() {
+        let container: Dimensions = Dimensions::new(10, 2, 10);
+        let items = vec![Dimensions::new(2, 10, 2)];
+
+        // Standing upright, the box sticks out of the top of its container
+        let result = extreme_point(container, Orientations::Upright, items.clone());
+        assert_eq!(10, result[0].contents()[0].height);
+        assert!(!result[0].contents()[0].rotated);
+
+        let result = extreme_point(container, Orientations::All, items);
+        assert_eq!(2, result[0].contents()[0].height);
+        assert!(result[0].contents()[0].rotated);
+    }
+
+    #[test]
+    fn never_overlaps() {
+        let container: Dimensions = Dimensions::new(20, 15, 30);
+
+        for orientations in [
+            Orientations::Fixed,
+            Orientations::Upright,
+            Orientations::All,
+        ] {
+            let result = extreme_point(container, orientations, random_boxes(200, 10));
+
+            assert_eq!(200, result.iter().map(Bin::len).sum::<usize>());
+            for bin in &result {
+                let cuboids = bin
+                    .iter()
+                    .map(|placement| {
+                        Cuboid::new(
+                            [placement.x, placement.y, placement.z],
+                            placement.dimensions(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                for (idx, a) in cuboids.iter().enumerate() {
+                    assert!(Dimensions::new(a.max[0], a.max[1], a.max[2]).fits_in(&container));
+                    for b in &cuboids[idx + 1..] {
+                        assert!(!a.intersects(b), "{a:?} overlaps {b:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let result = extreme_point_by_key(
+            Dimensions::new(2.0, 1.0, 1.0),
+            Orientations::Fixed,
+            vec![0.5, 1.5],
+            |&width| Dimensions::new(width, 1.0, 1.0),
+        );
+
+        assert_eq!(1, result.len());
+        assert_eq!(1.5, result[0].contents()[1].x);
+    }
+}
@@ -0,0 +1,170 @@
+//! Three-dimensional box packing (container loading),
+//! where boxes are put in containers without overlapping.
+//!
+//! Every container is returned as a [`Bin`](crate::Bin) of [`Placement`]s, which record where every box ended up.
+//! The capacity of a bin is the volume of the container, and its remaining capacity is the volume that isn't used,
+//! so [`Bin::fill_ratio`](crate::Bin::fill_ratio) is the part of the container that is used.
+//!
+//! The `y` axis points up, so the height of a box is along it,
+//! and [`Orientations::Upright`] only turns boxes around it.
+
+pub mod extreme_point;
+
+use std::ops::Mul;
+
+use crate::Size;
+
+/// The width, height and depth of a box.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Dimensions<S = usize> {
+    pub width: S,
+    pub height: S,
+    pub depth: S,
+}
+
+impl<S> Dimensions<S> {
+    /// Create new dimensions.
+    pub const fn new(width: S, height: S, depth: S) -> Self {
+        Self {
+            width,
+            height,
+            depth,
+        }
+    }
+
+    /// Get the volume of the box.
+    pub fn volume(self) -> S
+    where
+        S: Mul<Output = S>,
+    {
+        self.width * self.height * self.depth
+    }
+
+    /// Check whether a box of these dimensions fits in one of the `other` dimensions, without turning it.
+    pub fn fits_in(&self, other: &Self) -> bool
+    where
+        S: PartialOrd,
+    {
+        self.width <= other.width && self.height <= other.height && self.depth <= other.depth
+    }
+}
+
+/// Allows the box packing algorithms to know how big an item is.
+///
+/// This is the three-dimensional counterpart of [`crate::Pack`].
+pub trait PackBox<S = usize> {
+    /// Get the width, height and depth of the item.
+    fn dimensions(&self) -> Dimensions<S>;
+}
+
+impl<S> PackBox<S> for Dimensions<S>
+where
+    S: Copy,
+{
+    fn dimensions(&self) -> Dimensions<S> {
+        *self
+    }
+}
+
+/// The ways a box may be turned to make it fit better.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Orientations {
+    /// Boxes are always placed as they are.
+    #[default]
+    Fixed,
+    /// Boxes may be turned around the vertical axis, swapping their width and depth,
+    /// so "this side up" boxes stay upright.
+    Upright,
+    /// Boxes may be placed in any of the six orientations.
+    All,
+}
+
+impl Orientations {
+    /// The distinct ways a box of the given dimensions may be placed, starting with the box as it is.
+    fn of<S>(self, size: Dimensions<S>) -> Vec<Dimensions<S>>
+    where
+        S: Size,
+    {
+        let Dimensions {
+            width: w,
+            height: h,
+            depth: d,
+        } = size;
+
+        let candidates = match self {
+            Orientations::Fixed => vec![size],
+            Orientations::Upright => vec![size, Dimensions::new(d, h, w)],
+            Orientations::All => vec![
+                size,
+                Dimensions::new(d, h, w),
+                Dimensions::new(h, w, d),
+                Dimensions::new(h, d, w),
+                Dimensions::new(w, d, h),
+                Dimensions::new(d, w, h),
+            ],
+        };
+
+        let mut orientations = Vec::with_capacity(candidates.len());
+        for candidate in candidates {
+            if !orientations.contains(&candidate) {
+                orientations.push(candidate);
+            }
+        }
+
+        orientations
+    }
+}
+
+/// A box, together with where it was placed in a container.
+///
+/// The position is that of the corner of the box that is closest to the origin of the container.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Placement<T, S = usize> {
+    pub item: T,
+    pub x: S,
+    pub y: S,
+    pub z: S,
+    /// The width of the box as it was placed.
+    pub width: S,
+    /// The height of the box as it was placed.
+    pub height: S,
+    /// The depth of the box as it was placed.
+    pub depth: S,
+    /// Whether the box was turned.
+    pub rotated: bool,
+}
+
+impl<T, S> Placement<T, S>
+where
+    S: Copy,
+{
+    /// Get the dimensions of the box as it was placed.
+    pub fn dimensions(&self) -> Dimensions<S> {
+        Dimensions::new(self.width, self.height, self.depth)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn orientations() {
+        let size: Dimensions = Dimensions::new(1, 2, 3);
+
+        assert_eq!(vec![size], Orientations::Fixed.of(size));
+        assert_eq!(
+            vec![size, Dimensions::new(3, 2, 1)],
+            Orientations::Upright.of(size)
+        );
+        assert_eq!(6, Orientations::All.of(size).len());
+
+        // A cube can only be placed one way
+        assert_eq!(
+            1,
+            Orientations::All
+                .of(Dimensions::<usize>::new(2, 2, 2))
+                .len()
+        );
+    }
+}
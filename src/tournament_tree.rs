@@ -0,0 +1,141 @@
+use crate::Size;
+
+/// A max-tournament tree over the remaining capacity of a growing list of bins.
+///
+/// Every internal node holds the largest remaining capacity of the leaves below it,
+/// which lets [`TournamentTree::first_fit`] find the leftmost bin an item fits in
+/// in `O(log m)` instead of scanning all `m` bins.
+///
+/// Closed bins and unused leaves are `None`, so not even zero-sized items fit in them.
+#[doc(hidden)]
+#[derive(Debug)]
+pub(crate) struct TournamentTree<S> {
+    /// Binary heap layout: the root is at index 1, the children of `i` are `2i` and `2i + 1`,
+    /// and the leaves start at index `leaves`.
+    nodes: Vec<Option<S>>,
+    /// Number of leaves the tree has room for, always a power of two.
+    leaves: usize,
+    /// Number of leaves that are actually in use.
+    len: usize,
+}
+
+impl<S> TournamentTree<S>
+where
+    S: Size,
+{
+    /// Create a new tree with room for at least `capacity` bins before it has to grow.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        let leaves = capacity.max(1).next_power_of_two();
+
+        Self {
+            nodes: vec![None; 2 * leaves],
+            leaves,
+            len: 0,
+        }
+    }
+
+    /// Add a new bin with the given remaining capacity, returning its index.
+    pub(crate) fn push(&mut self, remaining_capacity: S) -> usize {
+        if self.len == self.leaves {
+            self.grow();
+        }
+
+        let index = self.len;
+        self.len += 1;
+        self.update(index, remaining_capacity);
+
+        index
+    }
+
+    /// Set the remaining capacity of the bin at `index`.
+    pub(crate) fn update(&mut self, index: usize, remaining_capacity: S) {
+        self.set(index, Some(remaining_capacity));
+    }
+
+    /// Close the bin at `index`, so that no more items are put in it.
+    pub(crate) fn close(&mut self, index: usize) {
+        self.set(index, None);
+    }
+
+    fn set(&mut self, index: usize, remaining_capacity: Option<S>) {
+        let mut node = self.leaves + index;
+        self.nodes[node] = remaining_capacity;
+
+        while node > 1 {
+            node /= 2;
+            self.nodes[node] = match (self.nodes[2 * node], self.nodes[2 * node + 1]) {
+                (Some(left), Some(right)) if right.total_cmp(&left).is_gt() => Some(right),
+                (Some(left), _) => Some(left),
+                (None, right) => right,
+            };
+        }
+    }
+
+    /// Check whether the bin (or subtree) at `node` has at least `size` remaining capacity.
+    fn fits(&self, node: usize, size: S) -> bool {
+        self.nodes[node].is_some_and(|remaining_capacity| remaining_capacity >= size)
+    }
+
+    /// Find the index of the first bin that has at least `size` remaining capacity.
+    pub(crate) fn first_fit(&self, size: S) -> Option<usize> {
+        if !self.fits(1, size) {
+            return None;
+        }
+
+        let mut node = 1;
+        while node < self.leaves {
+            node = if self.fits(2 * node, size) {
+                2 * node
+            } else {
+                2 * node + 1
+            };
+        }
+
+        Some(node - self.leaves)
+    }
+
+    /// Double the number of leaves, keeping the existing ones.
+    fn grow(&mut self) {
+        let mut grown = Self::with_capacity(self.leaves * 2);
+        for index in 0..self.len {
+            grown.set(index, self.nodes[self.leaves + index]);
+        }
+        grown.len = self.len;
+
+        *self = grown;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_leftmost_fitting_bin() {
+        let mut tree = TournamentTree::<usize>::with_capacity(1);
+        for remaining_capacity in [3, 8, 5, 10, 2] {
+            tree.push(remaining_capacity);
+        }
+
+        assert_eq!(Some(0), tree.first_fit(0));
+        assert_eq!(Some(0), tree.first_fit(3));
+        assert_eq!(Some(1), tree.first_fit(4));
+        assert_eq!(Some(3), tree.first_fit(9));
+        assert_eq!(None, tree.first_fit(11));
+
+        tree.update(1, 0);
+        assert_eq!(Some(2), tree.first_fit(4));
+
+        tree.close(0);
+        assert_eq!(Some(1), tree.first_fit(0));
+        assert_eq!(Some(2), tree.first_fit(3));
+    }
+
+    #[test]
+    fn empty_tree_fits_nothing() {
+        let tree = TournamentTree::<usize>::with_capacity(4);
+
+        assert_eq!(None, tree.first_fit(0));
+        assert_eq!(None, tree.first_fit(1));
+    }
+}
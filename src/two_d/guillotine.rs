@@ -0,0 +1,195 @@
+use std::ops::Mul;
+
+use crate::{Bin, Size};
+
+use super::{orientations, pack, Dimensions, PackRect, Placement, Position, Rect, Rotation, Sheet};
+
+/// Pack rectangles on sheets using the Guillotine algorithm,
+/// with the Best-area-fit and Shorter-leftover-axis rules.
+///
+/// The free space on every sheet is kept as a list of free rectangles that don't overlap.
+/// Every item is put in the corner of the smallest free rectangle it fits in,
+/// and the rest of that rectangle is cut in two along the axis where less space is left over.
+///
+/// Since every cut goes all the way through a free rectangle,
+/// the items can be cut from the sheet with edge-to-edge cuts, as with a guillotine.
+///
+/// Panics if the width or height of the `sheet` is 0.
+pub fn guillotine<T, S>(
+    sheet: Dimensions<S>,
+    rotation: Rotation,
+    items: Vec<T>,
+) -> Vec<Bin<Placement<T, S>, S>>
+where
+    T: PackRect<S>,
+    S: Size + Mul<Output = S>,
+{
+    guillotine_by_key(sheet, rotation, items, T::dimensions)
+}
+
+/// Pack rectangles on sheets using the Guillotine algorithm,
+/// with the Best-area-fit and Shorter-leftover-axis rules.
+///
+/// Unlike [`guillotine`], the items don't have to implement [`PackRect`].
+/// Instead, you need to provide a function that returns the dimensions of the item.
+pub fn guillotine_by_key<T, S, SizeFunc>(
+    sheet: Dimensions<S>,
+    rotation: Rotation,
+    items: Vec<T>,
+    key_func: SizeFunc,
+) -> Vec<Bin<Placement<T, S>, S>>
+where
+    S: Size + Mul<Output = S>,
+    SizeFunc: Fn(&T) -> Dimensions<S>,
+{
+    pack::<_, _, Guillotine<S>, _>(sheet, rotation, items, key_func)
+}
+
+/// The free rectangles on a sheet.
+#[derive(Debug)]
+struct Guillotine<S> {
+    free: Vec<Rect<S>>,
+}
+
+impl<S> Sheet<S> for Guillotine<S>
+where
+    S: Size + Mul<Output = S>,
+{
+    fn new(size: Dimensions<S>) -> Self {
+        Self {
+            free: vec![Rect {
+                x: S::ZERO,
+                y: S::ZERO,
+                width: size.width,
+                height: size.height,
+            }],
+        }
+    }
+
+    fn insert(&mut self, size: Dimensions<S>, rotation: Rotation) -> Option<Position<S>> {
+        // Find the smallest free rectangle the item fits in
+        let mut best: Option<(usize, Dimensions<S>, bool)> = None;
+        for (idx, free) in self.free.iter().enumerate() {
+            for (size, rotated) in orientations(size, rotation) {
+                if !size.fits_in(&free.dimensions()) {
+                    continue;
+                }
+
+                let improves = best.as_ref().is_none_or(|&(best_idx, _, _)| {
+                    free.dimensions()
+                        .area()
+                        .total_cmp(&self.free[best_idx].dimensions().area())
+                        .is_lt()
+                });
+                if improves {
+                    best = Some((idx, size, rotated));
+                }
+            }
+        }
+
+        let (idx, size, rotated) = best?;
+        let free = self.free.swap_remove(idx);
+        self.split(&free, size);
+
+        Some(Position {
+            x: free.x,
+            y: free.y,
+            rotated,
+        })
+    }
+}
+
+impl<S> Guillotine<S>
+where
+    S: Size,
+{
+    /// Cut the rest of the `free` rectangle, after putting an item of the given `size` in its corner,
+    /// in a rectangle above the item and one to the right of it.
+    fn split(&mut self, free: &Rect<S>, size: Dimensions<S>) {
+        let leftover_width = free.width - size.width;
+        let leftover_height = free.height - size.height;
+
+        // Cutting horizontally makes the part above the item span the whole width of the free rectangle
+        let horizontal_cut = leftover_width <= leftover_height;
+
+        let above = Rect {
+            x: free.x,
+            y: free.y + size.height,
+            width: if horizontal_cut {
+                free.width
+            } else {
+                size.width
+            },
+            height: leftover_height,
+        };
+        let right = Rect {
+            x: free.x + size.width,
+            y: free.y,
+            width: leftover_width,
+            height: if horizontal_cut {
+                size.height
+            } else {
+                free.height
+            },
+        };
+
+        for rect in [above, right] {
+            if rect.width > S::ZERO && rect.height > S::ZERO {
+                self.free.push(rect);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::two_d::tests::{assert_valid, random_rectangles};
+
+    #[test]
+    fn it_works() {
+        let sheet: Dimensions = Dimensions::new(10, 10);
+        let items = vec![
+            Dimensions::new(3, 3),
+            Dimensions::new(10, 6),
+            Dimensions::new(7, 4),
+        ];
+
+        let result = guillotine(sheet, Rotation::Never, items);
+
+        assert_eq!(1, result.len());
+        assert_eq!(
+            vec![(0, 0), (0, 6), (7, 6)],
+            result[0]
+                .iter()
+                .map(|placement| (placement.x, placement.y))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(3, result[0].remaining_capacity());
+    }
+
+    #[test]
+    fn never_overlaps() {
+        let sheet: Dimensions = Dimensions::new(100, 80);
+
+        for rotation in [Rotation::Never, Rotation::Allowed] {
+            let result = guillotine(sheet, rotation, random_rectangles(300, 40));
+
+            assert_valid(sheet, &result);
+            assert_eq!(300, result.iter().map(Bin::len).sum::<usize>());
+        }
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let result = guillotine_by_key(
+            Dimensions::new(2u64, 2),
+            Rotation::Allowed,
+            vec!["ab", "c", "d"],
+            |name| Dimensions::new(name.len() as u64, 1),
+        );
+
+        assert_eq!(1, result.len());
+        assert_eq!(0, result[0].remaining_capacity());
+    }
+}
@@ -0,0 +1,238 @@
+use std::cmp::Ordering;
+use std::ops::Mul;
+
+use crate::{Bin, Size};
+
+use super::{orientations, pack, Dimensions, PackRect, Placement, Position, Rect, Rotation, Sheet};
+
+/// Pack rectangles on sheets using the MaxRects algorithm by Jylänki,
+/// with the Best-short-side-fit rule.
+///
+/// The free space on every sheet is kept as the list of all maximal free rectangles, which may overlap.
+/// Every item is put in the corner of the free rectangle where the shorter leftover side is smallest.
+///
+/// This usually packs tighter than [`skyline`](super::skyline) and [`guillotine`](super::guillotine),
+/// but is slower when there are many items on a sheet.
+///
+/// Panics if the width or height of the `sheet` is 0.
+pub fn max_rects<T, S>(
+    sheet: Dimensions<S>,
+    rotation: Rotation,
+    items: Vec<T>,
+) -> Vec<Bin<Placement<T, S>, S>>
+where
+    T: PackRect<S>,
+    S: Size + Mul<Output = S>,
+{
+    max_rects_by_key(sheet, rotation, items, T::dimensions)
+}
+
+/// Pack rectangles on sheets using the MaxRects algorithm by Jylänki,
+/// with the Best-short-side-fit rule.
+///
+/// Unlike [`max_rects`], the items don't have to implement [`PackRect`].
+/// Instead, you need to provide a function that returns the dimensions of the item.
+pub fn max_rects_by_key<T, S, SizeFunc>(
+    sheet: Dimensions<S>,
+    rotation: Rotation,
+    items: Vec<T>,
+    key_func: SizeFunc,
+) -> Vec<Bin<Placement<T, S>, S>>
+where
+    S: Size + Mul<Output = S>,
+    SizeFunc: Fn(&T) -> Dimensions<S>,
+{
+    pack::<_, _, MaxRects<S>, _>(sheet, rotation, items, key_func)
+}
+
+/// The maximal free rectangles on a sheet.
+#[derive(Debug)]
+struct MaxRects<S> {
+    free: Vec<Rect<S>>,
+}
+
+impl<S> Sheet<S> for MaxRects<S>
+where
+    S: Size,
+{
+    fn new(size: Dimensions<S>) -> Self {
+        Self {
+            free: vec![Rect {
+                x: S::ZERO,
+                y: S::ZERO,
+                width: size.width,
+                height: size.height,
+            }],
+        }
+    }
+
+    fn insert(&mut self, size: Dimensions<S>, rotation: Rotation) -> Option<Position<S>> {
+        // Find the free rectangle and orientation with the smallest short leftover side,
+        // and then the smallest long leftover side
+        let mut best: Option<((S, S), Rect<S>, bool)> = None;
+        for free in &self.free {
+            for (size, rotated) in orientations(size, rotation) {
+                if !size.fits_in(&free.dimensions()) {
+                    continue;
+                }
+
+                let leftover_width = free.width - size.width;
+                let leftover_height = free.height - size.height;
+                let score = match leftover_width.total_cmp(&leftover_height) {
+                    Ordering::Greater => (leftover_height, leftover_width),
+                    _ => (leftover_width, leftover_height),
+                };
+
+                let improves = best.as_ref().is_none_or(|(best_score, _, _)| {
+                    score
+                        .0
+                        .total_cmp(&best_score.0)
+                        .then_with(|| score.1.total_cmp(&best_score.1))
+                        .is_lt()
+                });
+                if improves {
+                    let used = Rect {
+                        x: free.x,
+                        y: free.y,
+                        width: size.width,
+                        height: size.height,
+                    };
+                    best = Some((score, used, rotated));
+                }
+            }
+        }
+
+        let (_, used, rotated) = best?;
+        self.split(&used);
+
+        Some(Position {
+            x: used.x,
+            y: used.y,
+            rotated,
+        })
+    }
+}
+
+impl<S> MaxRects<S>
+where
+    S: Size,
+{
+    /// Replace every free rectangle that overlaps the `used` one by the (up to four) parts of it that are still free.
+    fn split(&mut self, used: &Rect<S>) {
+        let mut free = Vec::with_capacity(self.free.len());
+
+        for rect in self.free.drain(..) {
+            if !rect.intersects(used) {
+                free.push(rect);
+                continue;
+            }
+
+            if used.x > rect.x {
+                free.push(Rect {
+                    width: used.x - rect.x,
+                    ..rect
+                });
+            }
+            if used.right() < rect.right() {
+                free.push(Rect {
+                    x: used.right(),
+                    width: rect.right() - used.right(),
+                    ..rect
+                });
+            }
+            if used.y > rect.y {
+                free.push(Rect {
+                    height: used.y - rect.y,
+                    ..rect
+                });
+            }
+            if used.top() < rect.top() {
+                free.push(Rect {
+                    y: used.top(),
+                    height: rect.top() - used.top(),
+                    ..rect
+                });
+            }
+        }
+
+        // Only keep the maximal rectangles, and only one of any duplicates
+        let mut maximal = Vec::<Rect<S>>::with_capacity(free.len());
+        for (idx, rect) in free.iter().enumerate() {
+            let redundant = free.iter().enumerate().any(|(other_idx, other)| {
+                other.contains(rect) && (other != rect || other_idx < idx)
+            });
+            if !redundant {
+                maximal.push(*rect);
+            }
+        }
+
+        self.free = maximal;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::two_d::tests::{assert_valid, random_rectangles};
+
+    #[test]
+    fn it_works() {
+        let sheet: Dimensions = Dimensions::new(10, 10);
+        let items = vec![
+            Dimensions::new(6, 4),
+            Dimensions::new(4, 10),
+            Dimensions::new(6, 6),
+        ];
+
+        let result = max_rects(sheet, Rotation::Never, items);
+
+        assert_eq!(1, result.len());
+        assert_eq!(0, result[0].remaining_capacity());
+        assert_eq!(
+            vec![(0, 0), (4, 0), (4, 6)],
+            result[0]
+                .iter()
+                .map(|placement| (placement.x, placement.y))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn rotates_items_to_fit() {
+        let sheet: Dimensions = Dimensions::new(10, 4);
+        let items = vec![Dimensions::new(4, 6), Dimensions::new(4, 4)];
+
+        assert_eq!(2, max_rects(sheet, Rotation::Never, items.clone()).len());
+
+        let result = max_rects(sheet, Rotation::Allowed, items);
+
+        assert_eq!(1, result.len());
+        assert!(result[0].contents()[0].rotated);
+        assert_eq!(Dimensions::new(6, 4), result[0].contents()[0].dimensions());
+    }
+
+    #[test]
+    fn never_overlaps() {
+        let sheet: Dimensions = Dimensions::new(100, 80);
+
+        for rotation in [Rotation::Never, Rotation::Allowed] {
+            let result = max_rects(sheet, rotation, random_rectangles(300, 40));
+
+            assert_valid(sheet, &result);
+            assert_eq!(300, result.iter().map(Bin::len).sum::<usize>());
+        }
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let result = max_rects_by_key(
+            Dimensions::new(1.0, 1.0),
+            Rotation::Never,
+            vec![("a", 0.5, 1.0), ("b", 0.5, 0.5), ("c", 0.5, 0.5)],
+            |&(_, width, height)| Dimensions::new(width, height),
+        );
+
+        assert_eq!(1, result.len());
+        assert_eq!(1.0, result[0].fill_ratio());
+    }
+}
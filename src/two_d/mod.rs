@@ -0,0 +1,334 @@
+//! Two-dimensional [rectangle packing](https://en.wikipedia.org/wiki/Rectangle_packing),
+//! where rectangular items are put on rectangular sheets without overlapping,
+//! for example to build texture atlases or sprite sheets.
+//!
+//! Every sheet is returned as a [`Bin`] of [`Placement`]s, which record where every item ended up.
+//! The capacity of a bin is the area of the sheet, and its remaining capacity is the area that isn't covered,
+//! so [`Bin::fill_ratio`] is the part of the sheet that is used.
+//!
+//! The items are sorted by decreasing area before packing, and every item is put on the first sheet it fits on.
+//! Items that don't fit on a sheet at all, even when rotated,
+//! are put on a sheet of their own at the origin, sticking out of it.
+
+pub mod guillotine;
+pub mod max_rects;
+pub mod skyline;
+pub mod strip;
+
+use std::ops::Mul;
+
+use crate::{Bin, Size};
+
+/// The width and height of a rectangle.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Dimensions<S = usize> {
+    pub width: S,
+    pub height: S,
+}
+
+impl<S> Dimensions<S> {
+    /// Create new dimensions.
+    pub const fn new(width: S, height: S) -> Self {
+        Self { width, height }
+    }
+
+    /// Get the same rectangle, turned by 90°.
+    pub fn rotated(self) -> Self {
+        Self {
+            width: self.height,
+            height: self.width,
+        }
+    }
+
+    /// Get the area of the rectangle.
+    pub fn area(self) -> S
+    where
+        S: Mul<Output = S>,
+    {
+        self.width * self.height
+    }
+
+    /// Check whether a rectangle of these dimensions fits in one of the `other` dimensions, without rotating it.
+    pub fn fits_in(&self, other: &Self) -> bool
+    where
+        S: PartialOrd,
+    {
+        self.width <= other.width && self.height <= other.height
+    }
+}
+
+/// Allows the rectangle packing algorithms to know how big an item is.
+///
+/// This is the two-dimensional counterpart of [`crate::Pack`].
+pub trait PackRect<S = usize> {
+    /// Get the width and height of the item.
+    fn dimensions(&self) -> Dimensions<S>;
+}
+
+impl<S> PackRect<S> for Dimensions<S>
+where
+    S: Copy,
+{
+    fn dimensions(&self) -> Dimensions<S> {
+        *self
+    }
+}
+
+/// Whether items may be turned by 90° to make them fit better.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Rotation {
+    /// Items are always placed as they are.
+    #[default]
+    Never,
+    /// Items may be rotated.
+    Allowed,
+}
+
+/// An item, together with where it was placed on a sheet.
+///
+/// The position is that of the corner of the item that is closest to the origin of the sheet.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Placement<T, S = usize> {
+    pub item: T,
+    pub x: S,
+    pub y: S,
+    /// The width of the item as it was placed, which is its height if it was rotated.
+    pub width: S,
+    /// The height of the item as it was placed, which is its width if it was rotated.
+    pub height: S,
+    /// Whether the item was turned by 90°.
+    pub rotated: bool,
+}
+
+impl<T, S> Placement<T, S>
+where
+    S: Copy,
+{
+    /// Get the dimensions of the item as it was placed.
+    pub fn dimensions(&self) -> Dimensions<S> {
+        Dimensions::new(self.width, self.height)
+    }
+}
+
+/// Where a rectangle was put on a sheet.
+#[derive(Debug, Clone, Copy)]
+struct Position<S> {
+    x: S,
+    y: S,
+    rotated: bool,
+}
+
+/// A rectangle on a sheet.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Rect<S> {
+    x: S,
+    y: S,
+    width: S,
+    height: S,
+}
+
+impl<S> Rect<S>
+where
+    S: Size,
+{
+    fn right(&self) -> S {
+        self.x + self.width
+    }
+
+    fn top(&self) -> S {
+        self.y + self.height
+    }
+
+    fn dimensions(&self) -> Dimensions<S> {
+        Dimensions::new(self.width, self.height)
+    }
+
+    fn intersects(&self, other: &Self) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.top()
+            && other.y < self.top()
+    }
+
+    fn contains(&self, other: &Self) -> bool {
+        self.x <= other.x
+            && self.y <= other.y
+            && other.right() <= self.right()
+            && other.top() <= self.top()
+    }
+}
+
+/// The ways a rectangle may be placed: as it is, and rotated if that is allowed and makes a difference.
+fn orientations<S>(
+    size: Dimensions<S>,
+    rotation: Rotation,
+) -> impl Iterator<Item = (Dimensions<S>, bool)>
+where
+    S: Size,
+{
+    let rotated = (rotation == Rotation::Allowed && size.width != size.height)
+        .then(|| (size.rotated(), true));
+
+    std::iter::once((size, false)).chain(rotated)
+}
+
+/// The free space on a single sheet, as kept track of by one of the heuristics.
+trait Sheet<S>: Sized {
+    /// Create an empty sheet.
+    fn new(size: Dimensions<S>) -> Self;
+
+    /// Find a place for a rectangle and mark it as used, or return `None` if it doesn't fit.
+    fn insert(&mut self, size: Dimensions<S>, rotation: Rotation) -> Option<Position<S>>;
+}
+
+/// Pack the items on sheets, using the heuristic `H` to place the items on every sheet.
+fn pack<T, S, H, SizeFunc>(
+    sheet: Dimensions<S>,
+    rotation: Rotation,
+    items: Vec<T>,
+    key_func: SizeFunc,
+) -> Vec<Bin<Placement<T, S>, S>>
+where
+    S: Size + Mul<Output = S>,
+    H: Sheet<S>,
+    SizeFunc: Fn(&T) -> Dimensions<S>,
+{
+    assert!(
+        sheet.width > S::ZERO && sheet.height > S::ZERO,
+        "Sheet size must be greater than 0"
+    );
+
+    // Sort the items by decreasing area
+    let mut items = items
+        .into_iter()
+        .map(|item| (key_func(&item), item))
+        .collect::<Vec<_>>();
+    items.sort_by(|(a, _), (b, _)| b.area().total_cmp(&a.area()));
+
+    // Sheets that have an item that doesn't fit have no free space to keep track of
+    let mut sheets = Vec::<(Option<H>, Bin<Placement<T, S>, S>)>::new();
+
+    for (size, item) in items {
+        let placed = sheets.iter_mut().find_map(|(free, bin)| {
+            let position = free.as_mut()?.insert(size, rotation)?;
+            Some((bin, position))
+        });
+
+        let (bin, position) = match placed {
+            Some(placed) => placed,
+            None => {
+                let mut free = H::new(sheet);
+                let position = free.insert(size, rotation);
+                sheets.push((
+                    position.is_some().then_some(free),
+                    Bin::with_capacity(sheet.area()),
+                ));
+
+                let position = position.unwrap_or(Position {
+                    x: S::ZERO,
+                    y: S::ZERO,
+                    rotated: false,
+                });
+                let (_, bin) = sheets.last_mut().expect("A sheet was just added");
+                (bin, position)
+            }
+        };
+
+        let placed_size = if position.rotated {
+            size.rotated()
+        } else {
+            size
+        };
+        bin.add_with_size(
+            Placement {
+                item,
+                x: position.x,
+                y: position.y,
+                width: placed_size.width,
+                height: placed_size.height,
+                rotated: position.rotated,
+            },
+            size.area(),
+        );
+    }
+
+    sheets.into_iter().map(|(_, bin)| bin).collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::tests::random_numbers;
+
+    /// Pseudo-random rectangles between 1 and `max` wide and high.
+    pub fn random_rectangles(count: usize, max: usize) -> Vec<Dimensions> {
+        let mut numbers = random_numbers(42, max);
+        let mut next = move || numbers.next().expect("There are always more numbers") + 1;
+
+        (0..count)
+            .map(|_| Dimensions::new(next(), next()))
+            .collect()
+    }
+
+    /// Check that every item is placed on its sheet, without overlapping another item.
+    pub fn assert_valid(sheet: Dimensions, bins: &[Bin<Placement<Dimensions>>]) {
+        for bin in bins {
+            assert_placements_valid(sheet, bin.contents());
+        }
+    }
+
+    /// Check that every item is placed within `area`, without overlapping another item.
+    pub fn assert_placements_valid(area: Dimensions, placements: &[Placement<Dimensions>]) {
+        let rects = placements
+            .iter()
+            .map(|placement| Rect {
+                x: placement.x,
+                y: placement.y,
+                width: placement.width,
+                height: placement.height,
+            })
+            .collect::<Vec<_>>();
+
+        for (placement, rect) in placements.iter().zip(&rects) {
+            let expected = if placement.rotated {
+                placement.item.rotated()
+            } else {
+                placement.item
+            };
+            assert_eq!(expected, placement.dimensions());
+            assert!(rect.right() <= area.width && rect.top() <= area.height);
+        }
+
+        for (idx, a) in rects.iter().enumerate() {
+            for b in &rects[idx + 1..] {
+                assert!(!a.intersects(b), "{a:?} overlaps {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn dimensions() {
+        let size = Dimensions::new(3, 5);
+
+        assert_eq!(15, size.area());
+        assert_eq!(Dimensions::new(5, 3), size.rotated());
+        assert!(size.fits_in(&Dimensions::new(3, 6)));
+        assert!(!size.fits_in(&Dimensions::new(6, 3)));
+    }
+
+    #[test]
+    fn oversized_items_get_their_own_sheet() {
+        let sheet: Dimensions = Dimensions::new(10, 10);
+        let items = vec![Dimensions::new(2, 2), Dimensions::new(11, 1)];
+
+        let result = max_rects::max_rects(sheet, Rotation::Allowed, items);
+
+        assert_eq!(2, result.len());
+        assert_eq!(Dimensions::new(11, 1), result[0].contents()[0].item);
+        assert_eq!(
+            (0, 0),
+            (result[0].contents()[0].x, result[0].contents()[0].y)
+        );
+        assert_eq!(Dimensions::new(2, 2), result[1].contents()[0].item);
+    }
+}
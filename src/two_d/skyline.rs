@@ -0,0 +1,216 @@
+use std::ops::Mul;
+
+use crate::{Bin, Size};
+
+use super::{orientations, pack, Dimensions, PackRect, Placement, Position, Rotation, Sheet};
+
+/// Pack rectangles on sheets using the Skyline algorithm, with the Bottom-left rule.
+///
+/// Every sheet keeps track of the top edge of the items placed on it so far, the skyline.
+/// Every item is put on top of the skyline as low as possible, and then as far to the left as possible.
+/// The space below the skyline that isn't covered by an item is never used again.
+///
+/// This is fast and works well for items of similar heights.
+///
+/// Panics if the width or height of the `sheet` is 0.
+pub fn skyline<T, S>(
+    sheet: Dimensions<S>,
+    rotation: Rotation,
+    items: Vec<T>,
+) -> Vec<Bin<Placement<T, S>, S>>
+where
+    T: PackRect<S>,
+    S: Size + Mul<Output = S>,
+{
+    skyline_by_key(sheet, rotation, items, T::dimensions)
+}
+
+/// Pack rectangles on sheets using the Skyline algorithm, with the Bottom-left rule.
+///
+/// Unlike [`skyline`], the items don't have to implement [`PackRect`].
+/// Instead, you need to provide a function that returns the dimensions of the item.
+pub fn skyline_by_key<T, S, SizeFunc>(
+    sheet: Dimensions<S>,
+    rotation: Rotation,
+    items: Vec<T>,
+    key_func: SizeFunc,
+) -> Vec<Bin<Placement<T, S>, S>>
+where
+    S: Size + Mul<Output = S>,
+    SizeFunc: Fn(&T) -> Dimensions<S>,
+{
+    pack::<_, _, Skyline<S>, _>(sheet, rotation, items, key_func)
+}
+
+/// A horizontal part of the skyline.
+#[derive(Debug, Clone, Copy)]
+struct Segment<S> {
+    x: S,
+    y: S,
+    width: S,
+}
+
+/// The skyline of a sheet, as segments from left to right that together span the width of the sheet.
+#[derive(Debug)]
+struct Skyline<S> {
+    size: Dimensions<S>,
+    segments: Vec<Segment<S>>,
+}
+
+impl<S> Sheet<S> for Skyline<S>
+where
+    S: Size,
+{
+    fn new(size: Dimensions<S>) -> Self {
+        Self {
+            size,
+            segments: vec![Segment {
+                x: S::ZERO,
+                y: S::ZERO,
+                width: size.width,
+            }],
+        }
+    }
+
+    fn insert(&mut self, size: Dimensions<S>, rotation: Rotation) -> Option<Position<S>> {
+        // Find the lowest position, and then the leftmost one
+        let mut best: Option<(usize, Position<S>, Dimensions<S>)> = None;
+        for idx in 0..self.segments.len() {
+            for (size, rotated) in orientations(size, rotation) {
+                let Some(y) = self.fit(idx, size) else {
+                    continue;
+                };
+
+                let x = self.segments[idx].x;
+                let improves = best.as_ref().is_none_or(|(_, best, _)| {
+                    y.total_cmp(&best.y)
+                        .then_with(|| x.total_cmp(&best.x))
+                        .is_lt()
+                });
+                if improves {
+                    best = Some((idx, Position { x, y, rotated }, size));
+                }
+            }
+        }
+
+        let (idx, position, size) = best?;
+        self.add(idx, position.y + size.height, size.width);
+
+        Some(position)
+    }
+}
+
+impl<S> Skyline<S>
+where
+    S: Size,
+{
+    /// Find the height at which a rectangle would rest if its left edge is at the start of segment `idx`,
+    /// or `None` if it doesn't fit there.
+    fn fit(&self, idx: usize, size: Dimensions<S>) -> Option<S> {
+        let x = self.segments[idx].x;
+        if size.width > self.size.width - x {
+            return None;
+        }
+
+        // The rectangle rests on the highest segment below it
+        let mut y = S::ZERO;
+        let mut covered = S::ZERO;
+        for segment in &self.segments[idx..] {
+            if covered >= size.width {
+                break;
+            }
+            if segment.y > y {
+                y = segment.y;
+            }
+            covered = covered + segment.width;
+        }
+
+        (size.height <= self.size.height - y).then_some(y)
+    }
+
+    /// Raise the skyline to `top`, from the start of segment `idx` over the given `width`.
+    fn add(&mut self, idx: usize, top: S, width: S) {
+        let x = self.segments[idx].x;
+        let right = x + width;
+        self.segments.insert(idx, Segment { x, y: top, width });
+
+        // Shrink or remove the segments that are now covered
+        while let Some(segment) = self.segments.get_mut(idx + 1) {
+            if segment.x >= right {
+                break;
+            }
+
+            let covered = right - segment.x;
+            if covered < segment.width {
+                segment.x = right;
+                segment.width = segment.width - covered;
+                break;
+            }
+            self.segments.remove(idx + 1);
+        }
+
+        // Merge neighbouring segments at the same height
+        let mut merged = Vec::<Segment<S>>::with_capacity(self.segments.len());
+        for segment in self.segments.drain(..) {
+            match merged.last_mut() {
+                Some(last) if last.y == segment.y => last.width = last.width + segment.width,
+                _ => merged.push(segment),
+            }
+        }
+        self.segments = merged;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::two_d::tests::{assert_valid, random_rectangles};
+
+    #[test]
+    fn it_works() {
+        let sheet: Dimensions = Dimensions::new(10, 10);
+        let items = vec![
+            Dimensions::new(4, 3),
+            Dimensions::new(6, 5),
+            Dimensions::new(10, 5),
+            Dimensions::new(4, 2),
+        ];
+
+        let result = skyline(sheet, Rotation::Never, items);
+
+        // The 4x3 and 4x2 items are stacked next to the 6x5 item
+        assert_eq!(1, result.len());
+        assert_eq!(
+            vec![(0, 0), (0, 5), (6, 5), (6, 8)],
+            result[0]
+                .iter()
+                .map(|placement| (placement.x, placement.y))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn never_overlaps() {
+        let sheet: Dimensions = Dimensions::new(100, 80);
+
+        for rotation in [Rotation::Never, Rotation::Allowed] {
+            let result = skyline(sheet, rotation, random_rectangles(300, 40));
+
+            assert_valid(sheet, &result);
+            assert_eq!(300, result.iter().map(Bin::len).sum::<usize>());
+        }
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let result = skyline_by_key(
+            Dimensions::new(4u32, 2),
+            Rotation::Allowed,
+            vec![(1, 2), (2, 1), (3, 1)],
+            |&(width, height)| Dimensions::new(width, height),
+        );
+
+        assert_eq!(1, result.len());
+        assert_eq!(7, result[0].used());
+    }
+}
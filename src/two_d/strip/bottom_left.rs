@@ -0,0 +1,172 @@
+use crate::Size;
+
+use super::super::{Dimensions, PackRect, Placement, Rect, Rotation};
+use super::{assert_valid_width, oriented_items, Strip};
+
+/// Pack rectangles in a strip of the given `width` using the Bottom-left algorithm
+/// by Baker, Coffman and Rivest.
+///
+/// The items are sorted by decreasing width, and every item is put as low as possible in the strip,
+/// and then as far to the left as possible.
+/// Unlike the level algorithms, this can fill gaps next to and between earlier items.
+///
+/// The height used is never more than three times the optimal height.
+/// Packing `n` items takes O(n³) time, as every item is tried at the top of every earlier item.
+///
+/// Panics if `width` is 0.
+pub fn bottom_left<T, S>(width: S, rotation: Rotation, items: Vec<T>) -> Strip<T, S>
+where
+    T: PackRect<S>,
+    S: Size,
+{
+    bottom_left_by_key(width, rotation, items, T::dimensions)
+}
+
+/// Pack rectangles in a strip of the given `width` using the Bottom-left algorithm
+/// by Baker, Coffman and Rivest.
+///
+/// Unlike [`bottom_left`], the items don't have to implement [`PackRect`].
+/// Instead, you need to provide a function that returns the dimensions of the item.
+pub fn bottom_left_by_key<T, S, SizeFunc>(
+    width: S,
+    rotation: Rotation,
+    items: Vec<T>,
+    key_func: SizeFunc,
+) -> Strip<T, S>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> Dimensions<S>,
+{
+    assert_valid_width(width);
+
+    let mut items = oriented_items(width, rotation, items, key_func);
+    items.sort_by(|(a, _, _), (b, _, _)| b.width.total_cmp(&a.width));
+
+    // The placed items, sorted by their left edge
+    let mut placed = Vec::<Rect<S>>::with_capacity(items.len());
+    // The heights an item can be put at: the bottom of the strip and the top of every placed item,
+    // sorted and without duplicates
+    let mut ys = vec![S::ZERO];
+    let mut placements = Vec::with_capacity(items.len());
+
+    for (size, rotated, item) in items {
+        let rect = lowest_position(width, &placed, &ys, size);
+
+        let idx = placed.partition_point(|other| other.x <= rect.x);
+        placed.insert(idx, rect);
+        if let Err(idx) = ys.binary_search_by(|y| y.total_cmp(&rect.top())) {
+            ys.insert(idx, rect.top());
+        }
+
+        placements.push(Placement {
+            item,
+            x: rect.x,
+            y: rect.y,
+            width: size.width,
+            height: size.height,
+            rotated,
+        });
+    }
+
+    Strip::new(placements)
+}
+
+/// Find the lowest, and then leftmost, position for an item that doesn't overlap any of the `placed` items.
+///
+/// Such a position always has its bottom edge against the bottom of the strip or another item,
+/// so only the heights in `ys` are tried, from low to high.
+/// At every height, the items in the way are swept from left to right to find the leftmost gap.
+/// With `n` placed items, this takes O(n²) time.
+fn lowest_position<S>(width: S, placed: &[Rect<S>], ys: &[S], size: Dimensions<S>) -> Rect<S>
+where
+    S: Size,
+{
+    for &y in ys {
+        let in_band = placed
+            .iter()
+            .filter(|other| other.y < y + size.height && y < other.top());
+
+        // Every item that overlaps the position pushes it to its right edge
+        let mut x = S::ZERO;
+        for other in in_band {
+            if other.x >= x + size.width {
+                break;
+            }
+            if other.right() > x {
+                x = other.right();
+            }
+        }
+
+        if size.width <= width.saturating_sub(x) {
+            return Rect {
+                x,
+                y,
+                width: size.width,
+                height: size.height,
+            };
+        }
+    }
+
+    // The item is wider than the strip, so put it on top of everything else
+    Rect {
+        x: S::ZERO,
+        y: ys.last().copied().unwrap_or(S::ZERO),
+        width: size.width,
+        height: size.height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::two_d::tests::{assert_placements_valid, random_rectangles};
+
+    #[test]
+    fn it_works() {
+        let items: Vec<Dimensions> = vec![
+            Dimensions::new(6, 2),
+            Dimensions::new(5, 4),
+            Dimensions::new(4, 3),
+            Dimensions::new(3, 1),
+        ];
+
+        let result = bottom_left(10, Rotation::Never, items);
+
+        // The last item fills the gap between the 5x4 and the 4x3 item
+        assert_eq!(
+            vec![(0, 0), (0, 2), (6, 0), (5, 3)],
+            result
+                .placements
+                .iter()
+                .map(|placement| (placement.x, placement.y))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(6, result.height);
+    }
+
+    #[test]
+    fn never_overlaps() {
+        let items = random_rectangles(100, 40);
+
+        let result = bottom_left(100, Rotation::Allowed, items.clone());
+
+        assert_eq!(100, result.placements.len());
+        assert_placements_valid(Dimensions::new(100, result.height), &result.placements);
+    }
+
+    #[test]
+    fn too_wide_items_stick_out() {
+        let result = bottom_left_by_key(
+            10u32,
+            Rotation::Allowed,
+            vec![(4, 3), (12, 2)],
+            |&(width, height)| Dimensions::new(width, height),
+        );
+
+        // The widest item is packed first, against the left side of the strip
+        assert_eq!(12, result.placements[0].width);
+        assert_eq!((0, 0), (result.placements[0].x, result.placements[0].y));
+        assert_eq!((0, 2), (result.placements[1].x, result.placements[1].y));
+        assert_eq!(5, result.height);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::Size;
+
+use super::super::{Dimensions, PackRect, Rotation};
+use super::{pack_levels, Strip};
+
+/// Pack rectangles in a strip of the given `width` using the First-fit-decreasing-height (FFDH) algorithm.
+///
+/// The items are sorted by decreasing height and put next to each other on levels.
+/// Every item is put on the lowest level that has room for it,
+/// and if there is none, a new level is started on top, as high as that item.
+///
+/// The height used is never more than 1.7 times the optimal height plus the height of the tallest item.
+///
+/// Panics if `width` is 0.
+pub fn first_fit_decreasing_height<T, S>(width: S, rotation: Rotation, items: Vec<T>) -> Strip<T, S>
+where
+    T: PackRect<S>,
+    S: Size,
+{
+    first_fit_decreasing_height_by_key(width, rotation, items, T::dimensions)
+}
+
+/// Pack rectangles in a strip of the given `width` using the First-fit-decreasing-height (FFDH) algorithm.
+///
+/// Unlike [`first_fit_decreasing_height`], the items don't have to implement [`PackRect`].
+/// Instead, you need to provide a function that returns the dimensions of the item.
+pub fn first_fit_decreasing_height_by_key<T, S, SizeFunc>(
+    width: S,
+    rotation: Rotation,
+    items: Vec<T>,
+    key_func: SizeFunc,
+) -> Strip<T, S>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> Dimensions<S>,
+{
+    pack_levels(width, rotation, items, key_func, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::two_d::strip::next_fit_decreasing_height::next_fit_decreasing_height;
+    use crate::two_d::tests::{assert_placements_valid, random_rectangles};
+
+    #[test]
+    fn it_works() {
+        let items: Vec<Dimensions> = vec![
+            Dimensions::new(6, 2),
+            Dimensions::new(5, 4),
+            Dimensions::new(3, 3),
+            Dimensions::new(2, 1),
+        ];
+
+        let result = first_fit_decreasing_height(10, Rotation::Never, items);
+
+        // Unlike with NFDH, the last item goes back to the first level
+        assert_eq!(
+            vec![(0, 0), (5, 0), (0, 4), (8, 0)],
+            result
+                .placements
+                .iter()
+                .map(|placement| (placement.x, placement.y))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(6, result.height);
+    }
+
+    #[test]
+    fn never_higher_than_next_fit() {
+        let items = random_rectangles(300, 40);
+
+        let result = first_fit_decreasing_height(100, Rotation::Never, items.clone());
+
+        assert_placements_valid(Dimensions::new(100, result.height), &result.placements);
+        assert!(result.height <= next_fit_decreasing_height(100, Rotation::Never, items).height);
+    }
+
+    #[test]
+    fn rotates_items_on_their_side() {
+        let result = first_fit_decreasing_height_by_key(
+            10.0,
+            Rotation::Allowed,
+            vec![(2.0, 8.0), (2.0, 12.0)],
+            |&(width, height)| Dimensions::new(width, height),
+        );
+
+        // The second item is too tall to lie flat in the strip, so it is packed first, standing up
+        assert_eq!(12.0, result.height);
+        assert!(!result.placements[0].rotated);
+        assert!(result.placements[1].rotated);
+        assert_eq!(Dimensions::new(8.0, 2.0), result.placements[1].dimensions());
+        assert_eq!(2.0, result.placements[1].x);
+    }
+}
@@ -0,0 +1,134 @@
+//! [Strip packing](https://en.wikipedia.org/wiki/Strip_packing_problem),
+//! where rectangles are put in a strip of a fixed width, using as little of its height as possible.
+//!
+//! If rotation is allowed, items are turned so they are no taller than they are wide,
+//! as long as they still fit in the strip that way.
+//! Items that are wider than the strip are put against its left side, sticking out of it.
+
+pub mod bottom_left;
+pub mod first_fit_decreasing_height;
+pub mod next_fit_decreasing_height;
+
+use crate::Size;
+
+use super::{Dimensions, Placement, Rotation};
+
+/// The result of a strip packing algorithm.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Strip<T, S = usize> {
+    /// Where every item was placed in the strip.
+    pub placements: Vec<Placement<T, S>>,
+    /// The height of the strip that is used, up to the top of the highest item.
+    pub height: S,
+}
+
+impl<T, S> Strip<T, S>
+where
+    S: Size,
+{
+    fn new(placements: Vec<Placement<T, S>>) -> Self {
+        let height = placements
+            .iter()
+            .map(|placement| placement.y + placement.height)
+            .fold(
+                S::ZERO,
+                |height, top| if top > height { top } else { height },
+            );
+
+        Self { placements, height }
+    }
+}
+
+/// Panics if nothing fits in the strip.
+fn assert_valid_width<S>(width: S)
+where
+    S: Size,
+{
+    assert!(width > S::ZERO, "Strip width must be greater than 0");
+}
+
+/// Compute the size of every item, turning it on its side if rotation is allowed and it fits that way.
+///
+/// Returns whether every item was rotated along with its size.
+fn oriented_items<T, S>(
+    width: S,
+    rotation: Rotation,
+    items: Vec<T>,
+    key_func: impl Fn(&T) -> Dimensions<S>,
+) -> Vec<(Dimensions<S>, bool, T)>
+where
+    S: Size,
+{
+    items
+        .into_iter()
+        .map(|item| {
+            let size = key_func(&item);
+            let rotated =
+                rotation == Rotation::Allowed && size.height > size.width && size.height <= width;
+
+            if rotated {
+                (size.rotated(), true, item)
+            } else {
+                (size, false, item)
+            }
+        })
+        .collect()
+}
+
+/// Put the items on levels, in decreasing order of height.
+///
+/// Every level is as high as its first item. If `first_fit` is set, every item is put on the lowest level
+/// that has room for it, otherwise only the highest level is considered.
+fn pack_levels<T, S>(
+    width: S,
+    rotation: Rotation,
+    items: Vec<T>,
+    key_func: impl Fn(&T) -> Dimensions<S>,
+    first_fit: bool,
+) -> Strip<T, S>
+where
+    S: Size,
+{
+    assert_valid_width(width);
+
+    let mut items = oriented_items(width, rotation, items, key_func);
+    items.sort_by(|(a, _, _), (b, _, _)| b.height.total_cmp(&a.height));
+
+    // The bottom, height and used width of every level
+    let mut levels = Vec::<(S, S, S)>::new();
+    let mut placements = Vec::with_capacity(items.len());
+
+    for (size, rotated, item) in items {
+        let candidates = if first_fit {
+            0..levels.len()
+        } else {
+            levels.len().saturating_sub(1)..levels.len()
+        };
+        let level_idx = candidates
+            .into_iter()
+            .find(|&level_idx| size.width <= width.saturating_sub(levels[level_idx].2));
+
+        let level = match level_idx {
+            Some(level_idx) => &mut levels[level_idx],
+            None => {
+                let bottom = levels
+                    .last()
+                    .map_or(S::ZERO, |&(bottom, height, _)| bottom + height);
+                levels.push((bottom, size.height, S::ZERO));
+                levels.last_mut().expect("A level was just added")
+            }
+        };
+
+        placements.push(Placement {
+            item,
+            x: level.2,
+            y: level.0,
+            width: size.width,
+            height: size.height,
+            rotated,
+        });
+        level.2 = level.2 + size.width;
+    }
+
+    Strip::new(placements)
+}
@@ -0,0 +1,75 @@
+use crate::Size;
+
+use super::super::{Dimensions, PackRect, Rotation};
+use super::{pack_levels, Strip};
+
+/// Pack rectangles in a strip of the given `width` using the Next-fit-decreasing-height (NFDH) algorithm.
+///
+/// The items are sorted by decreasing height and put next to each other on levels.
+/// When an item doesn't fit on the current level, a new level is started on top of it,
+/// as high as that item.
+///
+/// The height used is never more than twice the optimal height plus the height of the tallest item.
+///
+/// Panics if `width` is 0.
+pub fn next_fit_decreasing_height<T, S>(width: S, rotation: Rotation, items: Vec<T>) -> Strip<T, S>
+where
+    T: PackRect<S>,
+    S: Size,
+{
+    next_fit_decreasing_height_by_key(width, rotation, items, T::dimensions)
+}
+
+/// Pack rectangles in a strip of the given `width` using the Next-fit-decreasing-height (NFDH) algorithm.
+///
+/// Unlike [`next_fit_decreasing_height`], the items don't have to implement [`PackRect`].
+/// Instead, you need to provide a function that returns the dimensions of the item.
+pub fn next_fit_decreasing_height_by_key<T, S, SizeFunc>(
+    width: S,
+    rotation: Rotation,
+    items: Vec<T>,
+    key_func: SizeFunc,
+) -> Strip<T, S>
+where
+    S: Size,
+    SizeFunc: Fn(&T) -> Dimensions<S>,
+{
+    pack_levels(width, rotation, items, key_func, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::two_d::tests::{assert_placements_valid, random_rectangles};
+
+    #[test]
+    fn it_works() {
+        let items: Vec<Dimensions> = vec![
+            Dimensions::new(6, 2),
+            Dimensions::new(5, 4),
+            Dimensions::new(3, 3),
+            Dimensions::new(2, 1),
+        ];
+
+        let result = next_fit_decreasing_height(10, Rotation::Never, items);
+
+        // The last item would fit next to the first two, but that level is already closed
+        assert_eq!(
+            vec![(0, 0), (5, 0), (0, 4), (6, 4)],
+            result
+                .placements
+                .iter()
+                .map(|placement| (placement.x, placement.y))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(6, result.height);
+    }
+
+    #[test]
+    fn never_overlaps() {
+        let result = next_fit_decreasing_height(100, Rotation::Allowed, random_rectangles(300, 40));
+
+        assert_eq!(300, result.placements.len());
+        assert_placements_valid(Dimensions::new(100, result.height), &result.placements);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::Bin;
+
+use super::{assert_valid_capacity, PackVector, ResourceVector};
+
+/// Pack items in bins using the [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing)
+/// bin packing algorithm, generalized to multiple dimensions.
+///
+/// An item is put in the first bin that it fits in, in every dimension.
+pub fn first_fit<T>(
+    capacity: T::Resources,
+    items: impl IntoIterator<Item = T>,
+) -> Vec<Bin<T, T::Resources>>
+where
+    T: PackVector,
+{
+    first_fit_by_key(capacity, items, T::size)
+}
+
+/// Pack items in bins using the [First-fit](https://en.wikipedia.org/wiki/First-fit_bin_packing)
+/// bin packing algorithm, generalized to multiple dimensions.
+///
+/// Unlike [`first_fit`], the items don't have to implement [`PackVector`].
+/// Instead, you need to provide a function that returns the size of the item in every dimension.
+pub fn first_fit_by_key<T, R, SizeFunc>(
+    capacity: R,
+    items: impl IntoIterator<Item = T>,
+    key_func: SizeFunc,
+) -> Vec<Bin<T, R>>
+where
+    R: ResourceVector,
+    SizeFunc: Fn(&T) -> R,
+{
+    assert_valid_capacity(&capacity);
+
+    __internal_first_fit(
+        capacity,
+        items.into_iter().map(|item| {
+            let size = key_func(&item);
+            (item, size)
+        }),
+    )
+}
+
+#[doc(hidden)]
+pub(crate) fn __internal_first_fit<T, R>(
+    capacity: R,
+    items: impl IntoIterator<Item = (T, R)>,
+) -> Vec<Bin<T, R>>
+where
+    R: ResourceVector,
+{
+    let mut bins = Vec::<Bin<T, R>>::new();
+
+    for (item, size) in items.into_iter() {
+        // Find the first bin that the item fits in
+        match bins.iter_mut().find(|bin| bin.fits_vector(&size)) {
+            Some(bin) => bin.add_with_vector_size(item, &size),
+            None => {
+                let mut bin = Bin::with_vector_capacity(capacity.clone());
+                bin.add_with_vector_size(item, &size);
+                bins.push(bin);
+            }
+        }
+    }
+
+    bins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::tests::{bin_sizes, items};
+
+    #[test]
+    fn it_works() {
+        let test_data = items(&[[5, 1], [1, 5], [5, 5], [4, 4], [1, 1]]);
+
+        let result = first_fit([10, 10], test_data);
+
+        // [5, 5] no longer fits next to [5, 1] and [1, 5], but [4, 4] does
+        assert_eq!(
+            vec![vec![[5, 1], [1, 5], [4, 4]], vec![[5, 5], [1, 1]]],
+            bin_sizes(&result)
+        );
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let test_data: Vec<(usize, usize)> = vec![(5, 1), (1, 5), (5, 5), (4, 4), (1, 1)];
+
+        let result = first_fit_by_key([10, 10], test_data, |&(cpu, memory)| [cpu, memory]);
+
+        assert_eq!(2, result.len());
+        assert_eq!(&[(5, 5), (1, 1)], result[1].contents());
+    }
+
+    #[test]
+    fn oversized_items_get_their_own_bin() {
+        let test_data = items(&[[1, 1], [11, 1], [1, 1]]);
+
+        let result = first_fit([10, 10], test_data);
+
+        assert_eq!(
+            vec![vec![[1, 1], [1, 1]], vec![[11, 1]]],
+            bin_sizes(&result)
+        );
+    }
+}
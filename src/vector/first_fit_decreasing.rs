@@ -0,0 +1,99 @@
+use crate::Bin;
+
+use super::first_fit::__internal_first_fit;
+use super::{assert_valid_capacity, Norm, PackVector, ResourceVector};
+
+/// Pack items in bins using the [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing)
+/// bin packing algorithm, generalized to multiple dimensions.
+///
+/// The items are sorted in decreasing order of their size according to the given [`Norm`].
+pub fn first_fit_decreasing<T>(
+    capacity: T::Resources,
+    items: Vec<T>,
+    norm: Norm,
+) -> Vec<Bin<T, T::Resources>>
+where
+    T: PackVector,
+{
+    first_fit_decreasing_by_key(capacity, items, norm, T::size)
+}
+
+/// Pack items in bins using the [First-fit-decreasing](https://en.wikipedia.org/wiki/First-fit-decreasing_bin_packing)
+/// bin packing algorithm, generalized to multiple dimensions.
+///
+/// Unlike [`first_fit_decreasing`], the items don't have to implement [`PackVector`].
+/// Instead, you need to provide a function that returns the size of the item in every dimension.
+pub fn first_fit_decreasing_by_key<T, R, SizeFunc>(
+    capacity: R,
+    items: Vec<T>,
+    norm: Norm,
+    key_func: SizeFunc,
+) -> Vec<Bin<T, R>>
+where
+    R: ResourceVector,
+    SizeFunc: Fn(&T) -> R,
+{
+    assert_valid_capacity(&capacity);
+
+    let mut items = items
+        .into_iter()
+        .map(|item| {
+            let size = key_func(&item);
+            (norm.apply(&size, &capacity), item, size)
+        })
+        .collect::<Vec<_>>();
+
+    // Sort the items in decreasing order
+    items.sort_by(|(a, ..), (b, ..)| b.total_cmp(a));
+
+    // Use the normal first fit implementation
+    __internal_first_fit(
+        capacity,
+        items.into_iter().map(|(_, item, size)| (item, size)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::tests::{bin_sizes, items};
+
+    #[test]
+    fn it_works() {
+        let test_data = items(&[[1, 1], [2, 8], [8, 2], [2, 2], [6, 6], [4, 4]]);
+
+        let result = first_fit_decreasing([10, 10], test_data, Norm::Max);
+
+        assert_eq!(
+            vec![
+                vec![[2, 8], [8, 2]],
+                vec![[6, 6], [4, 4]],
+                vec![[2, 2], [1, 1]],
+            ],
+            bin_sizes(&result)
+        );
+    }
+
+    #[test]
+    fn norm_changes_the_order() {
+        // By the largest dimension [9, 1] comes first, by the sum [5, 6] does
+        let result = first_fit_decreasing([10, 10], items(&[[5, 6], [9, 1]]), Norm::Max);
+        assert_eq!(vec![vec![[9, 1]], vec![[5, 6]]], bin_sizes(&result));
+
+        let result = first_fit_decreasing([10, 10], items(&[[5, 6], [9, 1]]), Norm::Sum);
+        assert_eq!(vec![vec![[5, 6]], vec![[9, 1]]], bin_sizes(&result));
+    }
+
+    #[test]
+    fn it_works_by_key() {
+        let test_data: Vec<(usize, usize)> = vec![(1, 1), (6, 6), (4, 4)];
+
+        let result =
+            first_fit_decreasing_by_key([10, 10], test_data, Norm::L2, |&(cpu, memory)| {
+                [cpu, memory]
+            });
+
+        assert_eq!(2, result.len());
+        assert_eq!(&[(6, 6), (4, 4)], result[0].contents());
+    }
+}
@@ -0,0 +1,211 @@
+//! [Vector bin packing](https://en.wikipedia.org/wiki/Vector_bin_packing),
+//! where items and bins have a size in several independent dimensions
+//! (for example CPU, memory and disk).
+//!
+//! An item only fits in a bin if it fits in every dimension.
+
+pub mod first_fit;
+pub mod first_fit_decreasing;
+pub mod next_k_fit;
+
+use crate::{Bin, Size};
+
+/// A size in several dimensions.
+///
+/// This is implemented for fixed-length arrays of any [`Size`] like `[usize; 3]`,
+/// but you can also implement it for your own resource type.
+pub trait ResourceVector: Clone {
+    /// The type of the size in a single dimension.
+    type Component: Size;
+
+    /// Get the number of dimensions.
+    fn dimensions(&self) -> usize;
+
+    /// Get the size in the given dimension.
+    fn get(&self, dimension: usize) -> Self::Component;
+
+    /// Get a mutable reference to the size in the given dimension.
+    fn get_mut(&mut self, dimension: usize) -> &mut Self::Component;
+}
+
+impl<S, const D: usize> ResourceVector for [S; D]
+where
+    S: Size,
+{
+    type Component = S;
+
+    fn dimensions(&self) -> usize {
+        D
+    }
+
+    fn get(&self, dimension: usize) -> S {
+        self[dimension]
+    }
+
+    fn get_mut(&mut self, dimension: usize) -> &mut S {
+        &mut self[dimension]
+    }
+}
+
+/// Allows the vector bin packing algorithms to know how big an item is in every dimension.
+///
+/// This is the multi-dimensional counterpart of [`crate::Pack`].
+pub trait PackVector {
+    /// The type of the size of the item.
+    type Resources: ResourceVector;
+
+    /// Get the size of the item in every dimension.
+    fn size(&self) -> Self::Resources;
+}
+
+/// How to turn the size of an item in every dimension into a single number,
+/// for example to sort items by.
+///
+/// Each dimension is first divided by the capacity of the bins in that dimension,
+/// so every dimension counts equally.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Norm {
+    /// The largest dimension.
+    Max,
+    /// The sum of all dimensions.
+    Sum,
+    /// The euclidean length.
+    L2,
+}
+
+impl Norm {
+    /// Compute the norm of `size`, relative to `capacity`.
+    pub fn apply<R>(self, size: &R, capacity: &R) -> f64
+    where
+        R: ResourceVector,
+    {
+        let relative = (0..capacity.dimensions())
+            .map(|dimension| size.get(dimension).to_f64() / capacity.get(dimension).to_f64());
+
+        match self {
+            Norm::Max => relative.fold(0.0, f64::max),
+            Norm::Sum => relative.sum(),
+            Norm::L2 => relative.map(|x| x * x).sum::<f64>().sqrt(),
+        }
+    }
+}
+
+/// Panics if the bins can't hold anything in some dimension.
+fn assert_valid_capacity<R>(capacity: &R)
+where
+    R: ResourceVector,
+{
+    assert!(
+        capacity.dimensions() > 0,
+        "Bins must have at least one dimension"
+    );
+    assert!(
+        (0..capacity.dimensions()).all(|dimension| capacity.get(dimension) > Size::ZERO),
+        "Bin size must be greater than 0 in every dimension"
+    );
+}
+
+/// Check whether `size` fits in `capacity` in every dimension.
+///
+/// Panics if they don't have the same number of dimensions.
+fn fits<R>(size: &R, capacity: &R) -> bool
+where
+    R: ResourceVector,
+{
+    assert_eq!(
+        size.dimensions(),
+        capacity.dimensions(),
+        "Item must have as many dimensions as the bin"
+    );
+
+    (0..size.dimensions()).all(|dimension| size.get(dimension) <= capacity.get(dimension))
+}
+
+impl<T, R> Bin<T, R>
+where
+    R: ResourceVector,
+{
+    /// Create a new empty bin with a size in every dimension.
+    #[doc(hidden)]
+    pub(crate) fn with_vector_capacity(capacity: R) -> Self {
+        let mut overflow = capacity.clone();
+        for dimension in 0..overflow.dimensions() {
+            *overflow.get_mut(dimension) = Size::ZERO;
+        }
+
+        Self {
+            contents: vec![],
+            capacity: capacity.clone(),
+            remaining_capacity: capacity,
+            overflow,
+            bin_type: None,
+        }
+    }
+
+    /// Check whether an item of the given size fits in this bin.
+    #[doc(hidden)]
+    pub(crate) fn fits_vector(&self, size: &R) -> bool {
+        fits(size, &self.remaining_capacity)
+    }
+
+    /// Add an item to this bin (given its size) and update the remaining capacity.
+    ///
+    /// Records the overflow in every dimension, like [`Bin::add`].
+    #[doc(hidden)]
+    pub(crate) fn add_with_vector_size(&mut self, item: T, size: &R) {
+        for dimension in 0..size.dimensions() {
+            let size = size.get(dimension);
+            let remaining = self.remaining_capacity.get(dimension);
+
+            if size > remaining {
+                let overflow = self.overflow.get_mut(dimension);
+                *overflow = *overflow + (size - remaining);
+                *self.remaining_capacity.get_mut(dimension) = Size::ZERO;
+            } else {
+                *self.remaining_capacity.get_mut(dimension) = remaining - size;
+            }
+        }
+
+        self.contents.push(item);
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// A dummy struct for testing, with a CPU and a memory size.
+    #[allow(dead_code)]
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct MyVectorItem {
+        pub size: [usize; 2],
+    }
+
+    impl PackVector for MyVectorItem {
+        type Resources = [usize; 2];
+
+        fn size(&self) -> [usize; 2] {
+            self.size
+        }
+    }
+
+    pub fn items(sizes: &[[usize; 2]]) -> Vec<MyVectorItem> {
+        sizes.iter().map(|&size| MyVectorItem { size }).collect()
+    }
+
+    pub fn bin_sizes(bins: &[Bin<MyVectorItem, [usize; 2]>]) -> Vec<Vec<[usize; 2]>> {
+        bins.iter()
+            .map(|bin| bin.contents().iter().map(|item| item.size).collect())
+            .collect()
+    }
+
+    #[test]
+    fn norms() {
+        let capacity: [usize; 2] = [10, 100];
+        let size = [3, 40];
+
+        assert_eq!(0.4, Norm::Max.apply(&size, &capacity));
+        assert_eq!(0.7, Norm::Sum.apply(&size, &capacity));
+        assert_eq!(0.5, Norm::L2.apply(&size, &capacity));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::online::online_packer::OnlinePackerError;
+use crate::online::OnlinePacker;
+use crate::Bin;
+
+use super::{assert_valid_capacity, fits, Norm, PackVector, ResourceVector};
+
+/// This implements the [Next-K-fit](https://en.wikipedia.org/wiki/Next-fit_bin_packing)
+/// bin packing algorithm, generalized to multiple dimensions.
+///
+/// A total of `K` bins are kept open.
+/// When a new item arrives, we attempt to put it into any one of the open bins.
+/// If none of the open bins are big enough in every dimension, the most-filled bin is closed,
+/// and a new bin is opened to hold the new item.
+///
+/// The most-filled bin is the one with the least remaining capacity according to [`Norm::Sum`].
+#[derive(Debug)]
+pub struct NextKFitPacker<Item, R, SizeFn> {
+    bins: Vec<Bin<Item, R>>,
+    capacity: R,
+    size_fn: SizeFn,
+}
+
+impl<Item, R, SizeFn> NextKFitPacker<Item, R, SizeFn>
+where
+    R: ResourceVector,
+{
+    /// Create a new NextKFitPacker.
+    ///
+    /// It will keep open `k` bins,
+    /// each of which will fit a maximum of `capacity` in every dimension.
+    ///
+    /// The size of a single element is determined by the `size_fn`.
+    ///
+    /// Panics if `k` is 0, or if `capacity` is 0 in any dimension.
+    pub fn new_with_key(k: usize, capacity: R, size_fn: SizeFn) -> Self {
+        assert_ne!(k, 0, "k must be greater than 0");
+        assert_valid_capacity(&capacity);
+
+        Self {
+            bins: (0..k)
+                .map(|_| Bin::with_vector_capacity(capacity.clone()))
+                .collect::<Vec<_>>(),
+            capacity,
+            size_fn,
+        }
+    }
+}
+
+impl<Item> NextKFitPacker<Item, Item::Resources, fn(&Item) -> Item::Resources>
+where
+    Item: PackVector,
+{
+    /// Create a new NextKFitPacker.
+    ///
+    /// This function requires that `Item` implements [`PackVector`].
+    /// If your type doesn't, consider using [`new_with_key`](NextKFitPacker::new_with_key).
+    pub fn new(k: usize, capacity: Item::Resources) -> Self {
+        NextKFitPacker::new_with_key(k, capacity, Item::size)
+    }
+}
+
+impl<Item, R, SizeFn> OnlinePacker<Item, R> for NextKFitPacker<Item, R, SizeFn>
+where
+    R: ResourceVector,
+    SizeFn: Fn(&Item) -> R,
+{
+    fn try_add(&mut self, item: Item) -> Result<Vec<Bin<Item, R>>, OnlinePackerError<Item>> {
+        let item_size = (self.size_fn)(&item);
+        if !fits(&item_size, &self.capacity) {
+            return Err(OnlinePackerError::ItemTooLarge(item));
+        }
+
+        // See if the item fits in any of the open bins.
+        if let Some(bin) = self.bins.iter_mut().find(|bin| bin.fits_vector(&item_size)) {
+            bin.add_with_vector_size(item, &item_size);
+            return Ok(Vec::new());
+        }
+
+        // The item didn't fit into any of the bins,
+        // so we need to:
+        // - open a new bin
+        // - put the new item in it
+        // - close the most-filled bin (and return it)
+        let most_filled_bin_idx = self
+            .bins
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                let a = Norm::Sum.apply(&a.remaining_capacity, &self.capacity);
+                let b = Norm::Sum.apply(&b.remaining_capacity, &self.capacity);
+                a.total_cmp(&b)
+            })
+            .map(|(bin_idx, _)| bin_idx)
+            .unwrap_or_default();
+
+        let mut bin = Bin::with_vector_capacity(self.capacity.clone());
+        bin.add_with_vector_size(item, &item_size);
+
+        std::mem::swap(&mut self.bins[most_filled_bin_idx], &mut bin);
+
+        Ok(vec![bin])
+    }
+
+    fn finalize(mut self) -> Vec<Bin<Item, R>> {
+        self.bins.retain(|bin| !bin.contents.is_empty());
+        self.bins
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::tests::{bin_sizes, items, MyVectorItem};
+
+    #[test]
+    fn empty_input_returns_no_bins() {
+        let packer: NextKFitPacker<MyVectorItem, _, _> = NextKFitPacker::new(2, [10, 10]);
+        assert_eq!(packer.finalize(), vec![]);
+    }
+
+    #[test]
+    fn closes_most_filled_bin() {
+        let mut packer = NextKFitPacker::new(2, [10, 10]);
+
+        assert!(packer.add(MyVectorItem { size: [8, 1] }).is_empty());
+        assert!(packer.add(MyVectorItem { size: [6, 1] }).is_empty());
+
+        // Fits in neither bin in the first dimension, so the fullest bin is closed
+        let closed = packer.add(MyVectorItem { size: [5, 5] });
+        assert_eq!(vec![vec![[8, 1]]], bin_sizes(&closed));
+
+        let remaining = packer.finalize();
+        assert_eq!(vec![vec![[5, 5]], vec![[6, 1]]], bin_sizes(&remaining));
+    }
+
+    #[test]
+    fn pack_all() {
+        let packer = NextKFitPacker::new(1, [10, 10]);
+
+        let bins = packer
+            .pack_all(items(&[[5, 1], [1, 5], [5, 5], [4, 4]]).into_iter())
+            .unwrap();
+
+        assert_eq!(
+            vec![vec![[5, 1], [1, 5]], vec![[5, 5], [4, 4]]],
+            bin_sizes(&bins)
+        );
+    }
+
+    #[test]
+    fn rejects_items_that_are_too_large() {
+        let mut packer = NextKFitPacker::new(2, [10, 10]);
+
+        assert!(matches!(
+            packer.try_add(MyVectorItem { size: [1, 11] }),
+            Err(OnlinePackerError::ItemTooLarge(_))
+        ));
+    }
+}
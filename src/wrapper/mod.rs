@@ -7,10 +7,9 @@ use std::ops::{Deref, DerefMut};
 /// before passing it to one of the packing functions,
 /// or else use the `by_key` versions of the packing functions
 /// (which are implemented using this wrapper).
-pub struct SizedWrapper<SizeFunc, T>
-where
-    SizeFunc: Fn(&T) -> usize,
-{
+///
+/// The wrapper implements [`crate::Pack`] for whatever [`crate::Size`] the function returns.
+pub struct SizedWrapper<SizeFunc, T> {
     pub key_func: SizeFunc,
     pub item: T,
 }
@@ -22,35 +21,39 @@ where
     pub fn new(key_func: SizeFunc, item: T) -> Self {
         Self { key_func, item }
     }
+}
+
+impl<SizeFunc, T> SizedWrapper<SizeFunc, T> {
+    /// Like [`SizedWrapper::new`], but for a key function that returns any [`crate::Size`].
+    pub fn new_generic<S>(key_func: SizeFunc, item: T) -> Self
+    where
+        SizeFunc: Fn(&T) -> S,
+    {
+        Self { key_func, item }
+    }
 
     pub fn take(self) -> T {
         self.item
     }
 }
 
-impl<SizeFunc, T> crate::Pack for SizedWrapper<SizeFunc, T>
+impl<SizeFunc, T, S> crate::Pack<S> for SizedWrapper<SizeFunc, T>
 where
-    SizeFunc: Fn(&T) -> usize,
+    SizeFunc: Fn(&T) -> S,
 {
-    fn size(&self) -> usize {
+    fn size(&self) -> S {
         (self.key_func)(&self.item)
     }
 }
 
-impl<F, T> Deref for SizedWrapper<F, T>
-where
-    F: Fn(&T) -> usize,
-{
+impl<F, T> Deref for SizedWrapper<F, T> {
     type Target = T;
     fn deref(&self) -> &Self::Target {
         &self.item
     }
 }
 
-impl<F, T> DerefMut for SizedWrapper<F, T>
-where
-    F: Fn(&T) -> usize,
-{
+impl<F, T> DerefMut for SizedWrapper<F, T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.item
     }
@@ -110,4 +113,13 @@ mod tests {
             std::mem::size_of_val(&wrapper)
         );
     }
+
+    #[test]
+    fn new_generic_accepts_any_size() {
+        use crate::Pack;
+
+        let wrapper = SizedWrapper::new_generic(|item: &f64| item * 2.0, 1.5);
+
+        assert_eq!(3.0, wrapper.size());
+    }
 }